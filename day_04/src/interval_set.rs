@@ -0,0 +1,249 @@
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    intervals: Vec<(u32, u32)>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, (start, end): (u32, u32)) {
+        if start > end {
+            return;
+        }
+
+        let first = self
+            .intervals
+            .partition_point(|&(_, e)| e.saturating_add(1) < start);
+        let last = self
+            .intervals
+            .partition_point(|&(s, _)| s <= end.saturating_add(1));
+
+        let mut merged = (start, end);
+        if first < last {
+            merged.0 = merged.0.min(self.intervals[first].0);
+            merged.1 = merged.1.max(self.intervals[last - 1].1);
+        }
+
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn merge(&self, other: &Self) -> Self {
+        self.intervals
+            .iter()
+            .chain(&other.intervals)
+            .copied()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = vec![];
+        let (mut i, mut j) = (0, 0);
+        while let (Some(&(start1, end1)), Some(&(start2, end2))) =
+            (self.intervals.get(i), other.intervals.get(j))
+        {
+            let start = start1.max(start2);
+            let end = end1.min(end2);
+            if start <= end {
+                intervals.push((start, end));
+            }
+
+            if end1 < end2 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut intervals = vec![];
+        let mut j = 0;
+        for &(start, end) in &self.intervals {
+            while other.intervals.get(j).is_some_and(|&(_, e)| e < start) {
+                j += 1;
+            }
+
+            let mut current = Some(start);
+            for &(other_start, other_end) in &other.intervals[j..] {
+                let Some(position) = current else { break };
+                if other_start > end {
+                    break;
+                }
+
+                if other_start > position {
+                    intervals.push((position, other_start - 1));
+                }
+                current = other_end.checked_add(1).filter(|&p| p <= end);
+            }
+
+            if let Some(position) = current {
+                intervals.push((position, end));
+            }
+        }
+
+        Self { intervals }
+    }
+
+    pub fn contains_range(&self, (start, end): (u32, u32)) -> bool {
+        if start > end {
+            return false;
+        }
+
+        let index = self.intervals.partition_point(|&(s, _)| s <= start);
+        index > 0 && self.intervals[index - 1].1 >= end
+    }
+
+    pub fn covered_length(&self) -> u64 {
+        self.intervals
+            .iter()
+            .map(|&(start, end)| (end - start) as u64 + 1)
+            .sum()
+    }
+
+    pub fn gaps(&self) -> Self {
+        Self {
+            intervals: self
+                .intervals
+                .windows(2)
+                .map(|w| (w[0].1 + 1, w[1].0 - 1))
+                .collect(),
+        }
+    }
+}
+
+impl FromIterator<(u32, u32)> for IntervalSet {
+    fn from_iter<T: IntoIterator<Item = (u32, u32)>>(iter: T) -> Self {
+        let mut ranges: Vec<(u32, u32)> = iter.into_iter().filter(|(s, e)| s <= e).collect();
+        ranges.sort_unstable();
+
+        let mut intervals: Vec<(u32, u32)> = vec![];
+        for (start, end) in ranges {
+            match intervals.last_mut() {
+                Some(last) if start <= last.1.saturating_add(1) => last.1 = last.1.max(end),
+                _ => intervals.push((start, end)),
+            }
+        }
+
+        Self { intervals }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(u32, u32)]) -> IntervalSet {
+        IntervalSet {
+            intervals: intervals.to_vec(),
+        }
+    }
+
+    #[test]
+    fn merges_adjacent_intervals() {
+        assert_eq!(set(&[(1, 3)]).merge(&set(&[(4, 6)])), set(&[(1, 6)]));
+
+        let mut inserted = IntervalSet::new();
+        inserted.insert((4, 6));
+        inserted.insert((1, 3));
+        inserted.insert((7, 7));
+        assert_eq!(inserted, set(&[(1, 7)]));
+    }
+
+    #[test]
+    fn merges_overlapping_intervals() {
+        assert_eq!(
+            set(&[(1, 5), (10, 12)]).merge(&set(&[(3, 8), (11, 20)])),
+            set(&[(1, 8), (10, 20)])
+        );
+
+        let mut inserted = set(&[(1, 2), (5, 6), (9, 10)]);
+        inserted.insert((2, 9));
+        assert_eq!(inserted, set(&[(1, 10)]));
+    }
+
+    #[test]
+    fn ignores_reversed_intervals() {
+        let mut inserted = set(&[(1, 2)]);
+        inserted.insert((5, 4));
+        assert_eq!(inserted, set(&[(1, 2)]));
+        assert_eq!(IntervalSet::from_iter([(5, 4)]), IntervalSet::new());
+    }
+
+    #[test]
+    fn intersects_interval_lists() {
+        assert_eq!(
+            set(&[(1, 5), (8, 12)]).intersection(&set(&[(4, 9), (12, 15)])),
+            set(&[(4, 5), (8, 9), (12, 12)])
+        );
+    }
+
+    #[test]
+    fn difference_splits_an_interval() {
+        assert_eq!(
+            set(&[(1, 10)]).difference(&set(&[(4, 6)])),
+            set(&[(1, 3), (7, 10)])
+        );
+        assert_eq!(
+            set(&[(1, 10)]).difference(&set(&[(0, 2), (5, 5), (9, 12)])),
+            set(&[(3, 4), (6, 8)])
+        );
+        assert_eq!(set(&[(3, 4)]).difference(&set(&[(1, 10)])), set(&[]));
+    }
+
+    #[test]
+    fn gaps_exclude_the_ends() {
+        assert_eq!(
+            set(&[(2, 3), (6, 8), (10, 10)]).gaps(),
+            set(&[(4, 5), (9, 9)])
+        );
+        assert_eq!(set(&[(2, 8)]).gaps(), set(&[]));
+        assert_eq!(IntervalSet::new().gaps(), set(&[]));
+    }
+
+    #[test]
+    fn measures_covered_length() {
+        assert_eq!(set(&[(1, 3), (5, 5)]).covered_length(), 4);
+        assert_eq!(IntervalSet::new().covered_length(), 0);
+    }
+
+    #[test]
+    fn handles_u32_bounds() {
+        let mut full = set(&[(0, 0)]);
+        full.insert((u32::MAX - 1, u32::MAX));
+        full.insert((1, u32::MAX - 2));
+        assert_eq!(full, set(&[(0, u32::MAX)]));
+        assert_eq!(full.covered_length(), 1 << 32);
+
+        assert_eq!(full.difference(&set(&[(5, u32::MAX)])), set(&[(0, 4)]));
+        assert_eq!(
+            set(&[(u32::MAX, u32::MAX)]).merge(&set(&[(0, 1)])),
+            set(&[(0, 1), (u32::MAX, u32::MAX)])
+        );
+        assert_eq!(
+            set(&[(0, 1), (u32::MAX, u32::MAX)]).gaps(),
+            set(&[(2, u32::MAX - 1)])
+        );
+        assert!(full.contains_range((u32::MAX, u32::MAX)));
+    }
+
+    #[test]
+    fn contains_ranges_within_one_interval() {
+        let intervals = set(&[(1, 3), (5, 9)]);
+        assert!(intervals.contains_range((5, 9)));
+        assert!(intervals.contains_range((6, 7)));
+        assert!(!intervals.contains_range((3, 5)));
+        assert!(!intervals.contains_range((0, 1)));
+        assert!(!intervals.contains_range((9, 10)));
+        assert!(!intervals.contains_range((4, 4)));
+    }
+
+    #[test]
+    fn does_not_contain_reversed_ranges() {
+        assert!(!set(&[(1, 9)]).contains_range((5, 4)));
+        assert!(!IntervalSet::new().contains_range((5, 4)));
+    }
+}
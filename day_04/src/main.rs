@@ -1,9 +1,28 @@
 use std::fs::read_to_string;
-use crate::parts::{part_1, part_2};
+use crate::parser::ParseError;
+use crate::parts::{count_common, count_covered_by_any_group, count_covered_more_than, count_covered_once, count_uncovered, find_largest_redundant, part_1, part_2};
 
+mod interval_set;
+mod parser;
 mod parts;
 
 fn main() {
     let input = read_to_string("input").unwrap();
-    println!("part 1: {} part 2: {}", part_1(&input), part_2(&input));
+    if let Err(error) = run(&input) {
+        eprintln!("invalid input: {error}");
+    }
+}
+
+fn run(input: &str) -> Result<(), ParseError> {
+    println!("part 1: {} part 2: {}", part_1(input)?, part_2(input)?);
+    println!(
+        "uncovered: {} covered once: {} covered more than once: {} common: {} largest redundant: {:?}",
+        count_uncovered(input)?,
+        count_covered_once(input)?,
+        count_covered_more_than(input, 1)?,
+        count_common(input)?,
+        find_largest_redundant(input)?
+    );
+    println!("covered in any group: {}", count_covered_by_any_group(input)?);
+    Ok(())
 }
@@ -0,0 +1,253 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+pub type Pair = ((u32, u32), (u32, u32));
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    Expected {
+        expected: &'static str,
+        found: Option<char>,
+    },
+    Overflow,
+    ReversedRange(u32, u32),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match self.kind {
+            ParseErrorKind::Expected {
+                expected,
+                found: Some(c),
+            } => write!(f, "expected {expected}, found {c:?}"),
+            ParseErrorKind::Expected {
+                expected,
+                found: None,
+            } => write!(f, "expected {expected}, found end of line"),
+            ParseErrorKind::Overflow => write!(f, "section number too large"),
+            ParseErrorKind::ReversedRange(start, end) => {
+                write!(f, "range {start}-{end} ends before it starts")
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+struct Cursor<'a> {
+    line: &'a str,
+    line_number: usize,
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(line: &'a str, line_number: usize) -> Self {
+        Cursor {
+            line,
+            line_number,
+            position: 0,
+        }
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.position == self.line.len()
+    }
+
+    fn error(&self, position: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            line: self.line_number,
+            column: position + 1,
+            kind,
+        }
+    }
+
+    fn expected(&self, expected: &'static str) -> ParseError {
+        let found = self.line[self.position..].chars().next();
+        self.error(self.position, ParseErrorKind::Expected { expected, found })
+    }
+
+    fn number(&mut self) -> Result<u32, ParseError> {
+        let start = self.position;
+        let mut value: u32 = 0;
+        while let Some(&digit) = self
+            .line
+            .as_bytes()
+            .get(self.position)
+            .filter(|b| b.is_ascii_digit())
+        {
+            value = value
+                .checked_mul(10)
+                .and_then(|v| v.checked_add((digit - b'0') as u32))
+                .ok_or_else(|| self.error(start, ParseErrorKind::Overflow))?;
+            self.position += 1;
+        }
+
+        if self.position == start {
+            return Err(self.expected("a section number"));
+        }
+
+        Ok(value)
+    }
+
+    fn symbol(&mut self, symbol: u8, expected: &'static str) -> Result<(), ParseError> {
+        if self.line.as_bytes().get(self.position) != Some(&symbol) {
+            return Err(self.expected(expected));
+        }
+
+        self.position += 1;
+        Ok(())
+    }
+
+    fn range(&mut self) -> Result<(u32, u32), ParseError> {
+        let position = self.position;
+        let start = self.number()?;
+        self.symbol(b'-', "'-'")?;
+        let end = self.number()?;
+        if start > end {
+            return Err(self.error(position, ParseErrorKind::ReversedRange(start, end)));
+        }
+
+        Ok((start, end))
+    }
+
+    fn end(&self) -> Result<(), ParseError> {
+        if !self.is_at_end() {
+            return Err(self.expected("end of line"));
+        }
+
+        Ok(())
+    }
+}
+
+pub fn parse_pair(line: &str, line_number: usize) -> Result<Pair, ParseError> {
+    let mut cursor = Cursor::new(line, line_number);
+    let first = cursor.range()?;
+    cursor.symbol(b',', "','")?;
+    let second = cursor.range()?;
+    cursor.end()?;
+    Ok((first, second))
+}
+
+pub fn parse_group(line: &str, line_number: usize) -> Result<Vec<(u32, u32)>, ParseError> {
+    let mut cursor = Cursor::new(line, line_number);
+    let mut group = vec![cursor.range()?];
+    while !cursor.is_at_end() {
+        cursor.symbol(b',', "',' or end of line")?;
+        group.push(cursor.range()?);
+    }
+
+    Ok(group)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(column: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            line: 7,
+            column,
+            kind,
+        }
+    }
+
+    fn expected(column: usize, expected: &'static str, found: Option<char>) -> ParseError {
+        error(column, ParseErrorKind::Expected { expected, found })
+    }
+
+    #[test]
+    fn parses_pairs_and_groups() {
+        assert_eq!(parse_pair("2-4,6-8", 7), Ok(((2, 4), (6, 8))));
+        assert_eq!(parse_group("2-4", 7), Ok(vec![(2, 4)]));
+        assert_eq!(
+            parse_group("2-4,6-8,1-1", 7),
+            Ok(vec![(2, 4), (6, 8), (1, 1)])
+        );
+    }
+
+    #[test]
+    fn reports_reversed_ranges_at_their_start() {
+        assert_eq!(
+            parse_pair("5-2,1-3", 7),
+            Err(error(1, ParseErrorKind::ReversedRange(5, 2)))
+        );
+        assert_eq!(
+            parse_pair("1-3,19-4", 7),
+            Err(error(5, ParseErrorKind::ReversedRange(19, 4)))
+        );
+        assert_eq!(
+            parse_group("1-3,4-5,9-8", 7),
+            Err(error(9, ParseErrorKind::ReversedRange(9, 8)))
+        );
+    }
+
+    #[test]
+    fn reports_missing_fields() {
+        assert_eq!(
+            parse_pair("", 7),
+            Err(expected(1, "a section number", None))
+        );
+        assert_eq!(parse_pair("1-3", 7), Err(expected(4, "','", None)));
+        assert_eq!(
+            parse_pair("1-,2-3", 7),
+            Err(expected(3, "a section number", Some(',')))
+        );
+        assert_eq!(parse_pair("1-3,2", 7), Err(expected(6, "'-'", None)));
+        assert_eq!(
+            parse_group("1-3,", 7),
+            Err(expected(5, "a section number", None))
+        );
+    }
+
+    #[test]
+    fn reports_extra_numbers() {
+        assert_eq!(
+            parse_pair("1-3,4-5,6-7", 7),
+            Err(expected(8, "end of line", Some(',')))
+        );
+        assert_eq!(
+            parse_pair("1-2-3,4-5", 7),
+            Err(expected(4, "','", Some('-')))
+        );
+        assert_eq!(
+            parse_group("1-2,3-4-5", 7),
+            Err(expected(8, "',' or end of line", Some('-')))
+        );
+    }
+
+    #[test]
+    fn reports_trailing_garbage() {
+        assert_eq!(
+            parse_pair("5-2,x,9", 7),
+            Err(error(1, ParseErrorKind::ReversedRange(5, 2)))
+        );
+        assert_eq!(
+            parse_pair("2-5,x,9", 7),
+            Err(expected(5, "a section number", Some('x')))
+        );
+        assert_eq!(
+            parse_pair("2-5,6-9 ", 7),
+            Err(expected(8, "end of line", Some(' ')))
+        );
+        assert_eq!(
+            parse_group("2-5,6-9x", 7),
+            Err(expected(8, "',' or end of line", Some('x')))
+        );
+    }
+
+    #[test]
+    fn reports_overflow_at_the_number() {
+        assert_eq!(
+            parse_pair("1-3,2-99999999999", 7),
+            Err(error(7, ParseErrorKind::Overflow))
+        );
+    }
+}
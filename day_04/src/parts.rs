@@ -1,4 +1,5 @@
-use regex::Regex;
+use crate::interval_set::IntervalSet;
+use crate::parser::{parse_group, parse_pair, Pair, ParseError};
 
 fn does_one_contain_other((start1, end1): (u32, u32), (start2, end2): (u32, u32)) -> bool {
     start1 <= start2 && end2 <= end1 ||
@@ -10,28 +11,112 @@ fn is_overlap((start1, end1): (u32, u32), (start2, end2): (u32, u32)) -> bool {
         start2 <= start1 && start1 <= end2
 }
 
-fn process_line(line: &str) -> ((u32, u32), (u32, u32)) {
-    let number_re = Regex::new(r"\d+").unwrap();
-    let numbers: Vec<u32> = number_re.find_iter(line).map(|m| m.as_str().parse().unwrap()).collect();
-    ((numbers[0], numbers[1]), (numbers[2], numbers[3]))
+fn process_input(input: &str) -> Result<Vec<Pair>, ParseError> {
+    input.lines()
+        .enumerate()
+        .map(|(i, line)| parse_pair(line, i + 1))
+        .collect()
 }
 
-fn process_input(input: &str) -> Vec<((u32, u32), (u32, u32))> {
+fn process_groups(input: &str) -> Result<Vec<Vec<(u32, u32)>>, ParseError> {
     input.lines()
-        .map(process_line)
+        .enumerate()
+        .map(|(i, line)| parse_group(line, i + 1))
         .collect()
 }
 
-pub fn part_1(input: &str) -> usize {
-    process_input(input)
+fn sections_covered_more_than(group: &[(u32, u32)], k: u32) -> IntervalSet {
+    let mut events: Vec<(u64, i64)> = group.iter()
+        .filter(|(start, end)| start <= end)
+        .flat_map(|&(start, end)| [(start as u64, 1), (end as u64 + 1, -1)])
+        .collect();
+    events.sort_unstable();
+
+    let mut output = IntervalSet::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (position, change) in events {
+        let was_covered = depth > k as i64;
+        depth += change;
+        let is_covered = depth > k as i64;
+        if !was_covered && is_covered {
+            start = position as u32;
+        } else if was_covered && !is_covered {
+            output.insert((start, (position - 1) as u32));
+        }
+    }
+
+    output
+}
+
+fn largest_redundant_assignment(group: &[(u32, u32)]) -> Option<(u32, u32)> {
+    let covered_twice = sections_covered_more_than(group, 1);
+    group.iter()
+        .copied()
+        .filter(|&assignment| covered_twice.contains_range(assignment))
+        .max_by_key(|&(start, end)| end - start)
+}
+
+fn common_sections(group: &[(u32, u32)]) -> IntervalSet {
+    group.iter()
+        .map(|&assignment| IntervalSet::from_iter([assignment]))
+        .reduce(|common, assignment| common.intersection(&assignment))
+        .unwrap_or_default()
+}
+
+pub fn part_1(input: &str) -> Result<usize, ParseError> {
+    Ok(process_input(input)?
         .iter()
         .filter(|(a, b)| does_one_contain_other(*a, *b))
-        .count()
+        .count())
 }
 
-pub fn part_2(input: &str) -> usize {
-    process_input(input)
+pub fn part_2(input: &str) -> Result<usize, ParseError> {
+    Ok(process_input(input)?
         .iter()
         .filter(|(a, b)| is_overlap(*a, *b))
-        .count()
-}
\ No newline at end of file
+        .count())
+}
+
+pub fn count_uncovered(input: &str) -> Result<u64, ParseError> {
+    Ok(process_groups(input)?
+        .into_iter()
+        .map(|group| IntervalSet::from_iter(group).gaps().covered_length())
+        .sum())
+}
+
+pub fn count_covered_once(input: &str) -> Result<u64, ParseError> {
+    Ok(process_groups(input)?
+        .iter()
+        .map(|group| sections_covered_more_than(group, 0).difference(&sections_covered_more_than(group, 1)).covered_length())
+        .sum())
+}
+
+pub fn count_covered_by_any_group(input: &str) -> Result<u64, ParseError> {
+    Ok(process_groups(input)?
+        .into_iter()
+        .map(IntervalSet::from_iter)
+        .fold(IntervalSet::new(), |covered, group| covered.merge(&group))
+        .covered_length())
+}
+
+pub fn count_covered_more_than(input: &str, k: u32) -> Result<u64, ParseError> {
+    Ok(process_groups(input)?
+        .iter()
+        .map(|group| sections_covered_more_than(group, k).covered_length())
+        .sum())
+}
+
+pub fn find_largest_redundant(input: &str) -> Result<Option<(u32, u32)>, ParseError> {
+    Ok(process_groups(input)?
+        .iter()
+        .filter_map(|group| largest_redundant_assignment(group))
+        .max_by_key(|&(start, end)| end - start))
+}
+
+pub fn count_common(input: &str) -> Result<u64, ParseError> {
+    Ok(process_groups(input)?
+        .iter()
+        .map(|group| common_sections(group).covered_length())
+        .sum())
+}
@@ -0,0 +1,336 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+pub type Stacks = Vec<Vec<String>>;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Instruction {
+    pub line: usize,
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+#[derive(Debug)]
+pub enum MoveErrorKind {
+    Malformed(String),
+    NoSuchStack(usize),
+    NotEnoughCrates { requested: usize, available: usize },
+}
+
+#[derive(Debug)]
+pub struct MoveError {
+    pub line: usize,
+    pub kind: MoveErrorKind,
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            MoveErrorKind::Malformed(text) => write!(f, "malformed instruction {text:?}"),
+            MoveErrorKind::NoSuchStack(stack) => write!(f, "stack {stack} does not exist"),
+            MoveErrorKind::NotEnoughCrates {
+                requested,
+                available,
+            } => write!(
+                f,
+                "cannot move {requested} crates from a stack holding {available}"
+            ),
+        }
+    }
+}
+
+impl Error for MoveError {}
+
+pub fn parse_instruction(text: &str, line: usize) -> Result<Instruction, MoveError> {
+    let malformed = || MoveError {
+        line,
+        kind: MoveErrorKind::Malformed(text.to_string()),
+    };
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let ["move", count, "from", from, "to", to] = words.as_slice() else {
+        return Err(malformed());
+    };
+
+    Ok(Instruction {
+        line,
+        count: count.parse().map_err(|_| malformed())?,
+        from: from.parse().map_err(|_| malformed())?,
+        to: to.parse().map_err(|_| malformed())?,
+    })
+}
+
+pub struct Move {
+    instruction: Instruction,
+    block: Vec<String>,
+}
+
+impl Move {
+    fn undo(self, stacks: &mut Stacks) {
+        let to = &mut stacks[self.instruction.to - 1];
+        to.truncate(to.len() - self.block.len());
+        stacks[self.instruction.from - 1].extend(self.block);
+    }
+}
+
+pub trait Crane {
+    fn arrange(&self, block: &mut Vec<String>);
+
+    fn apply(&self, stacks: &mut Stacks, instruction: &Instruction) -> Result<Move, MoveError> {
+        let error = |kind| MoveError {
+            line: instruction.line,
+            kind,
+        };
+        for stack in [instruction.from, instruction.to] {
+            if stack == 0 || stack > stacks.len() {
+                return Err(error(MoveErrorKind::NoSuchStack(stack)));
+            }
+        }
+
+        let from = &mut stacks[instruction.from - 1];
+        if instruction.count > from.len() {
+            return Err(error(MoveErrorKind::NotEnoughCrates {
+                requested: instruction.count,
+                available: from.len(),
+            }));
+        }
+
+        let block = from.split_off(from.len() - instruction.count);
+        let mut arranged = block.clone();
+        self.arrange(&mut arranged);
+        stacks[instruction.to - 1].extend(arranged);
+
+        Ok(Move {
+            instruction: *instruction,
+            block,
+        })
+    }
+
+    fn run(&self, mut stacks: Stacks, instructions: &[Instruction]) -> Result<Stacks, MoveError> {
+        for instruction in instructions {
+            self.apply(&mut stacks, instruction)?;
+        }
+
+        Ok(stacks)
+    }
+}
+
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn arrange(&self, block: &mut Vec<String>) {
+        block.reverse();
+    }
+}
+
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn arrange(&self, _block: &mut Vec<String>) {}
+}
+
+pub struct LimitedCrane {
+    capacity: usize,
+}
+
+impl LimitedCrane {
+    pub fn new(capacity: usize) -> Option<Self> {
+        (capacity > 0).then_some(LimitedCrane { capacity })
+    }
+}
+
+impl Crane for LimitedCrane {
+    fn arrange(&self, block: &mut Vec<String>) {
+        *block = block
+            .rchunks(self.capacity)
+            .flat_map(|chunk| chunk.iter().cloned())
+            .collect();
+    }
+}
+
+pub struct RotatingCrane {
+    pub turns: usize,
+}
+
+impl Crane for RotatingCrane {
+    fn arrange(&self, block: &mut Vec<String>) {
+        if !block.is_empty() {
+            let turns = self.turns % block.len();
+            block.rotate_right(turns);
+        }
+    }
+}
+
+pub struct Execution<'a, C: Crane> {
+    crane: &'a C,
+    stacks: Stacks,
+    log: Vec<Move>,
+}
+
+impl<'a, C: Crane> Execution<'a, C> {
+    pub fn new(crane: &'a C, stacks: Stacks) -> Self {
+        Execution {
+            crane,
+            stacks,
+            log: vec![],
+        }
+    }
+
+    pub fn stacks(&self) -> &Stacks {
+        &self.stacks
+    }
+
+    pub fn step(&mut self, instruction: &Instruction) -> Result<(), MoveError> {
+        let performed = self.crane.apply(&mut self.stacks, instruction)?;
+        self.log.push(performed);
+        Ok(())
+    }
+
+    pub fn execute(&mut self, instructions: &[Instruction]) -> Result<(), MoveError> {
+        instructions
+            .iter()
+            .try_for_each(|instruction| self.step(instruction))
+    }
+
+    pub fn undo(&mut self) -> Option<Instruction> {
+        let performed = self.log.pop()?;
+        let instruction = performed.instruction;
+        performed.undo(&mut self.stacks);
+        Some(instruction)
+    }
+
+    pub fn rewind_to(&mut self, step: usize) {
+        while self.log.len() > step {
+            self.undo();
+        }
+    }
+}
+
+pub struct Playback<'a, C: Crane> {
+    crane: &'a C,
+    stacks: Stacks,
+    instructions: std::slice::Iter<'a, Instruction>,
+}
+
+impl<'a, C: Crane> Playback<'a, C> {
+    pub fn new(crane: &'a C, stacks: Stacks, instructions: &'a [Instruction]) -> Self {
+        Playback {
+            crane,
+            stacks,
+            instructions: instructions.iter(),
+        }
+    }
+}
+
+impl<C: Crane> Iterator for Playback<'_, C> {
+    type Item = Result<Stacks, MoveError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let instruction = self.instructions.next()?;
+        match self.crane.apply(&mut self.stacks, instruction) {
+            Ok(_) => Some(Ok(self.stacks.clone())),
+            Err(error) => {
+                self.instructions = [].iter();
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_stacks() -> Stacks {
+        [&["Z", "N"][..], &["M", "C", "D"], &["P"]]
+            .iter()
+            .map(|stack| stack.iter().map(|label| label.to_string()).collect())
+            .collect()
+    }
+
+    fn sample_instructions() -> Vec<Instruction> {
+        [(1, 2, 1), (3, 1, 3), (2, 2, 1), (1, 1, 2)]
+            .iter()
+            .enumerate()
+            .map(|(index, &(count, from, to))| Instruction {
+                line: index + 6,
+                count,
+                from,
+                to,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rewinding_restores_every_prefix() {
+        let instructions = sample_instructions();
+        let mut snapshots = vec![sample_stacks()];
+        let mut execution = Execution::new(&CrateMover9000, sample_stacks());
+        for instruction in &instructions {
+            execution.step(instruction).unwrap();
+            snapshots.push(execution.stacks().clone());
+        }
+
+        for step in (0..instructions.len()).rev() {
+            execution.rewind_to(step);
+            assert_eq!(execution.stacks(), &snapshots[step]);
+        }
+        assert_eq!(execution.undo().map(|instruction| instruction.line), None);
+    }
+
+    #[test]
+    fn rewinding_then_replaying_reaches_the_same_state() {
+        let instructions = sample_instructions();
+        let mut execution = Execution::new(&CrateMover9001, sample_stacks());
+        execution.execute(&instructions).unwrap();
+        let finished = execution.stacks().clone();
+
+        execution.rewind_to(1);
+        assert_eq!(
+            execution.stacks(),
+            &CrateMover9001
+                .run(sample_stacks(), &instructions[..1])
+                .unwrap()
+        );
+        execution.execute(&instructions[1..]).unwrap();
+        assert_eq!(execution.stacks(), &finished);
+    }
+
+    #[test]
+    fn undo_returns_instructions_in_reverse() {
+        let instructions = sample_instructions();
+        let mut execution = Execution::new(&CrateMover9000, sample_stacks());
+        execution.execute(&instructions).unwrap();
+
+        let undone: Vec<usize> = std::iter::from_fn(|| execution.undo())
+            .map(|instruction| instruction.line)
+            .collect();
+        assert_eq!(undone, vec![9, 8, 7, 6]);
+        assert_eq!(execution.stacks(), &sample_stacks());
+    }
+
+    #[test]
+    fn failed_steps_leave_the_stacks_unchanged() {
+        let mut execution = Execution::new(&CrateMover9000, sample_stacks());
+        let error = execution
+            .step(&Instruction {
+                line: 3,
+                count: 4,
+                from: 2,
+                to: 1,
+            })
+            .unwrap_err();
+
+        assert_eq!(error.line, 3);
+        assert!(matches!(
+            error.kind,
+            MoveErrorKind::NotEnoughCrates {
+                requested: 4,
+                available: 3
+            }
+        ));
+        assert_eq!(execution.stacks(), &sample_stacks());
+        assert!(execution.undo().is_none());
+    }
+}
@@ -0,0 +1,355 @@
+use crate::crane::Stacks;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DrawingError {
+    MissingNumbering,
+    UnexpectedStackNumber {
+        column: usize,
+        expected: usize,
+    },
+    UnterminatedCrate {
+        line: usize,
+        column: usize,
+    },
+    UnexpectedCharacter {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    DuplicateCrate {
+        line: usize,
+        column: usize,
+        stack: usize,
+    },
+    FloatingCrate {
+        line: usize,
+        column: usize,
+        stack: usize,
+    },
+}
+
+impl Display for DrawingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DrawingError::MissingNumbering => write!(f, "drawing has no stack numbering line"),
+            DrawingError::UnexpectedStackNumber { column, expected } => write!(
+                f,
+                "numbering line, column {column}: expected stack number {expected}"
+            ),
+            DrawingError::UnterminatedCrate { line, column } => {
+                write!(f, "line {line}, column {column}: crate is missing its ']'")
+            }
+            DrawingError::UnexpectedCharacter {
+                line,
+                column,
+                found,
+            } => write!(f, "line {line}, column {column}: unexpected {found:?}"),
+            DrawingError::DuplicateCrate {
+                line,
+                column,
+                stack,
+            } => write!(
+                f,
+                "line {line}, column {column}: second crate in the same row of stack {stack}"
+            ),
+            DrawingError::FloatingCrate {
+                line,
+                column,
+                stack,
+            } => write!(
+                f,
+                "line {line}, column {column}: crate above an empty slot in stack {stack}"
+            ),
+        }
+    }
+}
+
+impl Error for DrawingError {}
+
+struct Token {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+impl Token {
+    fn doubled_center(&self) -> usize {
+        self.start + self.end
+    }
+}
+
+fn get_numbers(line: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = vec![];
+    let mut previous_is_space = true;
+    for (column, c) in line.chars().enumerate() {
+        if c.is_whitespace() {
+            previous_is_space = true;
+            continue;
+        }
+
+        match tokens.last_mut() {
+            Some(token) if !previous_is_space => {
+                token.end = column + 1;
+                token.text.push(c);
+            }
+            _ => tokens.push(Token {
+                start: column,
+                end: column + 1,
+                text: c.to_string(),
+            }),
+        }
+        previous_is_space = false;
+    }
+
+    tokens
+}
+
+fn get_crates(line: &str, line_number: usize) -> Result<Vec<Token>, DrawingError> {
+    let mut tokens = vec![];
+    let mut chars = line.chars().enumerate();
+    while let Some((column, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        if c != '[' {
+            return Err(DrawingError::UnexpectedCharacter {
+                line: line_number,
+                column: column + 1,
+                found: c,
+            });
+        }
+
+        let mut text = String::new();
+        let end = loop {
+            match chars.next() {
+                Some((end, ']')) => break end + 1,
+                Some((_, c)) => text.push(c),
+                None => {
+                    return Err(DrawingError::UnterminatedCrate {
+                        line: line_number,
+                        column: column + 1,
+                    })
+                }
+            }
+        };
+
+        tokens.push(Token {
+            start: column,
+            end,
+            text,
+        });
+    }
+
+    Ok(tokens)
+}
+
+pub fn parse(drawing: &str) -> Result<Stacks, DrawingError> {
+    let lines: Vec<&str> = drawing.lines().collect();
+    let (numbering, crate_lines) = lines.split_last().ok_or(DrawingError::MissingNumbering)?;
+
+    let numbers = get_numbers(numbering);
+    if numbers.is_empty() {
+        return Err(DrawingError::MissingNumbering);
+    }
+
+    for (index, number) in numbers.iter().enumerate() {
+        if number.text.parse() != Ok(index + 1) {
+            return Err(DrawingError::UnexpectedStackNumber {
+                column: number.start + 1,
+                expected: index + 1,
+            });
+        }
+    }
+
+    let mut stacks: Stacks = vec![vec![]; numbers.len()];
+    for (height, (index, line)) in crate_lines.iter().enumerate().rev().enumerate() {
+        let line_number = index + 1;
+        for token in get_crates(line, line_number)? {
+            let stack = (0..numbers.len())
+                .min_by_key(|&i| numbers[i].doubled_center().abs_diff(token.doubled_center()))
+                .unwrap();
+
+            let column = token.start + 1;
+            if stacks[stack].len() > height {
+                return Err(DrawingError::DuplicateCrate {
+                    line: line_number,
+                    column,
+                    stack: stack + 1,
+                });
+            }
+
+            if stacks[stack].len() < height {
+                return Err(DrawingError::FloatingCrate {
+                    line: line_number,
+                    column,
+                    stack: stack + 1,
+                });
+            }
+
+            stacks[stack].push(token.text);
+        }
+    }
+
+    Ok(stacks)
+}
+
+pub fn render(stacks: &Stacks) -> String {
+    let label_width = stacks
+        .iter()
+        .flatten()
+        .map(|label| label.chars().count() + 2)
+        .max()
+        .unwrap_or(0);
+    let width = label_width.max(stacks.len().to_string().len());
+
+    let height = stacks.iter().map(|stack| stack.len()).max().unwrap_or(0);
+    let mut lines: Vec<String> = (0..height)
+        .rev()
+        .map(|row| {
+            stacks
+                .iter()
+                .map(|stack| match stack.get(row) {
+                    Some(label) => format!("{:^width$}", format!("[{label}]")),
+                    None => " ".repeat(width),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+
+    lines.push(
+        (1..=stacks.len())
+            .map(|n| format!("{n:^width$}"))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stacks(labels: &[&[&str]]) -> Stacks {
+        labels
+            .iter()
+            .map(|stack| stack.iter().map(|label| label.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn round_trips_more_than_nine_stacks() {
+        let text = [
+            "[J]                                    ",
+            "[A] [B] [C] [D] [E] [F] [G] [H] [I] [K]",
+            " 1   2   3   4   5   6   7   8   9  10 ",
+        ]
+        .join("\n");
+        let expected = stacks(&[
+            &["A", "J"],
+            &["B"],
+            &["C"],
+            &["D"],
+            &["E"],
+            &["F"],
+            &["G"],
+            &["H"],
+            &["I"],
+            &["K"],
+        ]);
+
+        assert_eq!(parse(&text), Ok(expected.clone()));
+        assert_eq!(render(&expected), text);
+    }
+
+    #[test]
+    fn round_trips_multi_character_labels() {
+        let text = [" [C]       ", "[AB]  [DEF]", "  1     2  "].join("\n");
+        let expected = stacks(&[&["AB", "C"], &["DEF"]]);
+
+        assert_eq!(parse(&text), Ok(expected.clone()));
+        assert_eq!(render(&expected), text);
+    }
+
+    #[test]
+    fn renders_ragged_lines_padded() {
+        let ragged = ["    [D]", "[N] [C]", "[Z] [M] [P]", " 1   2   3"].join("\n");
+        let padded = ["    [D]    ", "[N] [C]    ", "[Z] [M] [P]", " 1   2   3 "].join("\n");
+        let expected = stacks(&[&["Z", "N"], &["M", "C", "D"], &["P"]]);
+
+        assert_eq!(parse(&ragged), Ok(expected.clone()));
+        assert_eq!(render(&expected), padded);
+        assert_eq!(render(&parse(&padded).unwrap()), padded);
+    }
+
+    #[test]
+    fn round_trips_empty_stacks() {
+        let expected = stacks(&[&[], &["X"], &[]]);
+        assert_eq!(parse(&render(&expected)), Ok(expected));
+    }
+
+    #[test]
+    fn reports_missing_numbering() {
+        assert_eq!(parse(""), Err(DrawingError::MissingNumbering));
+        assert_eq!(parse("[A]\n   "), Err(DrawingError::MissingNumbering));
+    }
+
+    #[test]
+    fn reports_unexpected_stack_number() {
+        assert_eq!(
+            parse("[A] [B]\n 1   3 "),
+            Err(DrawingError::UnexpectedStackNumber {
+                column: 6,
+                expected: 2
+            })
+        );
+    }
+
+    #[test]
+    fn reports_unterminated_crate() {
+        assert_eq!(
+            parse("[A] [B\n 1   2 "),
+            Err(DrawingError::UnterminatedCrate { line: 1, column: 5 })
+        );
+    }
+
+    #[test]
+    fn reports_unexpected_character() {
+        assert_eq!(
+            parse("[A] B\n 1   2 "),
+            Err(DrawingError::UnexpectedCharacter {
+                line: 1,
+                column: 5,
+                found: 'B'
+            })
+        );
+    }
+
+    #[test]
+    fn reports_duplicate_crate() {
+        assert_eq!(
+            parse("[A][B]\n 1 "),
+            Err(DrawingError::DuplicateCrate {
+                line: 1,
+                column: 4,
+                stack: 1
+            })
+        );
+    }
+
+    #[test]
+    fn reports_floating_crate() {
+        assert_eq!(
+            parse("    [A]\n[B]    \n 1   2 "),
+            Err(DrawingError::FloatingCrate {
+                line: 1,
+                column: 5,
+                stack: 2
+            })
+        );
+    }
+}
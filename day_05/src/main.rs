@@ -1,104 +1,99 @@
+use crate::crane::{
+    parse_instruction, Crane, CrateMover9000, CrateMover9001, Execution, Instruction, LimitedCrane,
+    MoveError, Playback, RotatingCrane, Stacks,
+};
+use crate::drawing::{parse, render};
+use std::error::Error;
 use std::fs::read_to_string;
 
-fn main() {
-    let input = read_to_string("input").unwrap();
-    let input = process_input(&input);
+mod crane;
+mod drawing;
 
-    println!(
-        "part 1: {} part 2: {}",
-        part_1(input.clone()),
-        part_2(input)
-    );
-}
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let step = match args.as_slice() {
+        [] => None,
+        [step] if step.parse::<usize>().is_ok() => step.parse().ok(),
+        _ => {
+            eprintln!("usage: day_5 [step]");
+            return;
+        }
+    };
 
-fn part_1((mut stacks, instructions): (Vec<Vec<char>>, Vec<(u32, usize, usize)>)) -> String {
-    for (count, from, to) in instructions {
-        move_crates(&mut stacks, count, from, to);
-    }
+    let input = read_to_string("input").unwrap();
+    let (stacks, instructions) = match process_input(&input) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            eprintln!("invalid input: {error}");
+            return;
+        }
+    };
 
-    let mut output = "".to_string();
-    for stack in stacks {
-        output.push(stack.last().unwrap().clone());
+    if let Err(error) = run(stacks, &instructions, step) {
+        eprintln!("invalid rearrangement: {error}");
     }
-
-    output
 }
 
-fn part_2((mut stacks, instructions): (Vec<Vec<char>>, Vec<(u32, usize, usize)>)) -> String {
-    for (count, from, to) in instructions {
-        move_multiple_crates(&mut stacks, count, from, to);
+fn run(stacks: Stacks, instructions: &[Instruction], step: Option<usize>) -> Result<(), MoveError> {
+    let mut execution = Execution::new(&CrateMover9001, stacks.clone());
+    if let Err(error) = execution.execute(instructions) {
+        println!("{}", render(execution.stacks()));
+        return Err(error);
     }
 
-    let mut output = "".to_string();
-    for stack in stacks {
-        output.push(stack.last().unwrap().clone());
-    }
+    println!(
+        "part 1: {} part 2: {}",
+        get_top_crates(&CrateMover9000, stacks.clone(), instructions)?,
+        get_top_crates(&CrateMover9001, stacks.clone(), instructions)?
+    );
 
-    output
-}
-fn move_crates(stacks: &mut Vec<Vec<char>>, count: u32, from: usize, to: usize) {
-    let from = from - 1;
-    let to = to - 1;
-    for _ in 0..count {
-        let current_crate = stacks[from].pop().unwrap();
-        stacks[to].push(current_crate);
-    }
-}
+    println!(
+        "limited to 3: {} rotating by 1: {}",
+        get_top_crates(&LimitedCrane::new(3).unwrap(), stacks.clone(), instructions)?,
+        get_top_crates(&RotatingCrane { turns: 1 }, stacks.clone(), instructions)?
+    );
 
-fn move_multiple_crates(stacks: &mut Vec<Vec<char>>, count: u32, from: usize, to: usize) {
-    let from = from - 1;
-    let to = to - 1;
-    let mut crates = vec![];
-    for _ in 0..count {
-        crates.push(stacks[from].pop().unwrap())
-    }
+    let tallest = Playback::new(&CrateMover9001, stacks, instructions)
+        .map(|stacks| stacks.map(|stacks| stacks.iter().map(|stack| stack.len()).max()))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or(0);
+    println!("tallest stack during rearrangement: {tallest}");
 
-    for _ in 0..count {
-        stacks[to].push(crates.pop().unwrap());
+    if let Some(step) = step {
+        execution.rewind_to(step);
     }
-}
+    println!("{}", render(execution.stacks()));
 
-fn process_input(input: &str) -> (Vec<Vec<char>>, Vec<(u32, usize, usize)>) {
-    let crates: Vec<_> = input
-        .lines()
-        .take_while(|l| l.chars().nth(1).unwrap() != '1')
-        .collect();
+    Ok(())
+}
 
-    let crates: Vec<_> = crates
+fn get_top_crates(
+    crane: &impl Crane,
+    stacks: Stacks,
+    instructions: &[Instruction],
+) -> Result<String, MoveError> {
+    Ok(crane
+        .run(stacks, instructions)?
         .into_iter()
-        .rev()
-        .map(|line| line.chars().skip(1).step_by(4).collect::<String>())
-        .collect();
+        .filter_map(|stack| stack.last().cloned())
+        .collect())
+}
 
-    let mut stacks: Vec<Vec<char>> = vec![vec![]; crates.first().unwrap().len()];
-    for row in crates {
-        for (index, current_crate) in row.chars().enumerate() {
-            if current_crate != ' ' {
-                stacks[index].push(current_crate);
-            }
-        }
-    }
+fn process_input(input: &str) -> Result<(Stacks, Vec<Instruction>), Box<dyn Error>> {
+    let drawing = input.split("\n\n").next().unwrap();
+    let stacks = parse(drawing)?;
 
-    let instructions: Vec<&str> = input
+    let instructions = input
         .lines()
-        .skip_while(|line| !line.is_empty())
+        .enumerate()
+        .skip_while(|(_, line)| !line.is_empty())
         .skip(1)
-        .collect();
-
-    let instructions = instructions
-        .into_iter()
-        .map(|line| {
-            line.split_whitespace()
-                .filter(|s| s.chars().next().unwrap().is_digit(10))
-                .collect::<Vec<_>>()
-        })
-        .map(|v| {
-            let count = v[0].parse().unwrap();
-            let from = v[1].parse().unwrap();
-            let to = v[2].parse().unwrap();
-            (count, from, to)
-        })
-        .collect();
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| parse_instruction(line, index + 1))
+        .collect::<Result<_, _>>()?;
 
-    (stacks, instructions)
+    Ok((stacks, instructions))
 }
@@ -1,23 +1,82 @@
-use std::collections::HashSet;
-use std::fs::read_to_string;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+
+struct MarkerDetector {
+    length: usize,
+    window: VecDeque<u8>,
+    counts: [usize; 256],
+    duplicates: usize,
+}
+
+impl MarkerDetector {
+    fn new(length: usize) -> Self {
+        assert!(length > 0, "marker length must be positive");
+        MarkerDetector {
+            length,
+            window: VecDeque::with_capacity(length),
+            counts: [0; 256],
+            duplicates: 0,
+        }
+    }
+
+    fn push(&mut self, byte: u8) -> bool {
+        if self.window.len() == self.length {
+            let oldest = self.window.pop_front().unwrap() as usize;
+            if self.counts[oldest] == 2 {
+                self.duplicates -= 1;
+            }
+            self.counts[oldest] -= 1;
+        }
+
+        self.window.push_back(byte);
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.duplicates += 1;
+        }
+
+        self.window.len() == self.length && self.duplicates == 0
+    }
+}
 
 fn main() {
-    let input = read_to_string("input").unwrap();
-    let output_1 = get_marker_position(&input, 4);
-    let output_2 = get_marker_position(&input, 14);
+    let markers = find_markers(File::open("input").unwrap(), &[4, 14]).unwrap();
+    let output_1 = format_position(markers[0].first());
+    let output_2 = format_position(markers[1].first());
     println!("part 1: {output_1} part 2: {output_2}");
+    println!(
+        "packet markers: {} message markers: {}",
+        markers[0].len(),
+        markers[1].len()
+    );
 }
 
-fn is_unique(input: &[char]) -> bool {
-    let mut seen = HashSet::new();
-    input.iter().all(|&c| seen.insert(c))
+fn format_position(position: Option<&usize>) -> String {
+    position.map_or("none".to_string(), |p| p.to_string())
 }
 
-fn get_marker_position(input: &str, length: usize) -> usize {
-    input.chars()
-        .collect::<Vec<char>>()
-        .windows(length)
-        .position(is_unique)
-        .unwrap()
-    + length
-}
\ No newline at end of file
+fn find_markers(mut reader: impl Read, lengths: &[usize]) -> io::Result<Vec<Vec<usize>>> {
+    let mut detectors: Vec<MarkerDetector> =
+        lengths.iter().map(|&l| MarkerDetector::new(l)).collect();
+    let mut markers = vec![vec![]; lengths.len()];
+    let mut buffer = [0; 8192];
+    let mut position = 0;
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(markers),
+            Ok(read) => read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+
+        for &byte in &buffer[..read] {
+            position += 1;
+            for (detector, positions) in detectors.iter_mut().zip(&mut markers) {
+                if detector.push(byte) {
+                    positions.push(position);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::BTreeMap;
+
+pub type NodeId = usize;
+
+pub enum NodeKind {
+    Directory(BTreeMap<String, NodeId>),
+    File,
+}
+
+pub struct Node {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    pub kind: NodeKind,
+    pub size: u64,
+}
+
+pub struct FileSystem {
+    nodes: Vec<Node>,
+}
+
+impl FileSystem {
+    pub const ROOT: NodeId = 0;
+
+    pub fn new() -> Self {
+        FileSystem {
+            nodes: vec![Node {
+                name: String::new(),
+                parent: None,
+                kind: NodeKind::Directory(BTreeMap::new()),
+                size: 0,
+            }],
+        }
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id]
+    }
+
+    pub fn is_directory(&self, id: NodeId) -> bool {
+        matches!(self.nodes[id].kind, NodeKind::Directory(_))
+    }
+
+    pub fn children(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let children = match &self.nodes[id].kind {
+            NodeKind::Directory(children) => Some(children.values().copied()),
+            NodeKind::File => None,
+        };
+        children.into_iter().flatten()
+    }
+
+    pub fn child(&self, id: NodeId, name: &str) -> Option<NodeId> {
+        match &self.nodes[id].kind {
+            NodeKind::Directory(children) => children.get(name).copied(),
+            NodeKind::File => None,
+        }
+    }
+
+    fn insert(&mut self, parent: NodeId, name: &str, kind: NodeKind, size: u64) -> NodeId {
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(parent),
+            kind,
+            size: 0,
+        });
+
+        self.attach(id, parent);
+        self.adjust_size(id, size as i64);
+
+        id
+    }
+
+    fn attach(&mut self, id: NodeId, parent: NodeId) {
+        let name = self.nodes[id].name.clone();
+        if let NodeKind::Directory(children) = &mut self.nodes[parent].kind {
+            children.insert(name, id);
+        }
+        self.nodes[id].parent = Some(parent);
+    }
+
+    fn detach(&mut self, id: NodeId) {
+        let Some(parent) = self.nodes[id].parent.take() else {
+            return;
+        };
+        let name = self.nodes[id].name.clone();
+        if let NodeKind::Directory(children) = &mut self.nodes[parent].kind {
+            children.remove(&name);
+        }
+    }
+
+    fn adjust_size(&mut self, id: NodeId, delta: i64) {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            self.nodes[id].size = self.nodes[id].size.checked_add_signed(delta).unwrap();
+            current = self.nodes[id].parent;
+        }
+    }
+
+    pub fn make_directory(&mut self, parent: NodeId, name: &str) -> NodeId {
+        match self.child(parent, name) {
+            Some(id) => id,
+            None => self.insert(parent, name, NodeKind::Directory(BTreeMap::new()), 0),
+        }
+    }
+
+    pub fn add_file(&mut self, parent: NodeId, name: &str, size: u64) -> NodeId {
+        match self.child(parent, name) {
+            Some(id) => id,
+            None => self.insert(parent, name, NodeKind::File, size),
+        }
+    }
+
+    pub fn set_file_size(&mut self, id: NodeId, size: u64) {
+        let delta = size as i64 - self.nodes[id].size as i64;
+        self.adjust_size(id, delta);
+    }
+
+    pub fn remove(&mut self, id: NodeId) {
+        let size = self.nodes[id].size;
+        self.adjust_size(id, -(size as i64));
+        self.detach(id);
+    }
+
+    pub fn move_node(&mut self, id: NodeId, parent: NodeId, name: &str) {
+        let size = self.nodes[id].size as i64;
+        self.adjust_size(id, -size);
+        self.detach(id);
+        self.nodes[id].name = name.to_string();
+        self.attach(id, parent);
+        self.adjust_size(id, size);
+    }
+
+    pub fn is_ancestor(&self, ancestor: NodeId, id: NodeId) -> bool {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            if id == ancestor {
+                return true;
+            }
+            current = self.nodes[id].parent;
+        }
+
+        false
+    }
+
+    fn descendants(&self, id: NodeId) -> Vec<NodeId> {
+        let mut output = vec![];
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            output.push(id);
+            stack.extend(self.children(id));
+        }
+
+        output
+    }
+
+    pub fn directories(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.descendants(Self::ROOT)
+            .into_iter()
+            .filter(|&id| self.is_directory(id))
+    }
+
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = vec![];
+        let mut current = id;
+        while let Some(parent) = self.nodes[current].parent {
+            names.push(self.nodes[current].name.as_str());
+            current = parent;
+        }
+
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    pub fn resolve(&self, from: NodeId, path: &str) -> Option<NodeId> {
+        let (mut current, path) = match path.strip_prefix('/') {
+            Some(path) => (Self::ROOT, path),
+            None => (from, path),
+        };
+
+        for segment in path.split('/') {
+            current = match segment {
+                "" | "." => current,
+                ".." => self.nodes[current].parent.unwrap_or(Self::ROOT),
+                name => self.child(current, name)?,
+            };
+        }
+
+        Some(current)
+    }
+
+    pub fn find(&self, name: &str) -> Vec<NodeId> {
+        self.descendants(Self::ROOT)
+            .into_iter()
+            .filter(|&id| id != Self::ROOT && self.nodes[id].name == name)
+            .collect()
+    }
+
+    pub fn du(&self, path: &str) -> Option<u64> {
+        self.resolve(Self::ROOT, path).map(|id| self.nodes[id].size)
+    }
+
+    pub fn largest_directories(&self, count: usize) -> Vec<NodeId> {
+        let mut directories: Vec<NodeId> = self.directories().collect();
+        directories.sort_by_key(|&id| std::cmp::Reverse(self.nodes[id].size));
+        directories.truncate(count);
+        directories
+    }
+
+    pub fn tree(&self) -> String {
+        let mut output = format!("/ ({})\n", self.nodes[Self::ROOT].size);
+        self.tree_lines(Self::ROOT, "", &mut output);
+        output
+    }
+
+    fn tree_lines(&self, id: NodeId, prefix: &str, output: &mut String) {
+        let children: Vec<NodeId> = self.children(id).collect();
+        for (index, &child) in children.iter().enumerate() {
+            let is_last = index + 1 == children.len();
+            let node = &self.nodes[child];
+            let branch = if is_last { "└── " } else { "├── " };
+            let suffix = if self.is_directory(child) { "/" } else { "" };
+            output.push_str(&format!(
+                "{prefix}{branch}{}{suffix} ({})\n",
+                node.name, node.size
+            ));
+
+            let extension = if is_last { "    " } else { "│   " };
+            self.tree_lines(child, &format!("{prefix}{extension}"), output);
+        }
+    }
+}
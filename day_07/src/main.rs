@@ -1,123 +1,109 @@
-use std::collections::{HashMap, HashSet};
+use crate::filesystem::FileSystem;
+use crate::planner::{plan, PlanOptions};
+use crate::transcript::replay;
 use std::fs::read_to_string;
 
-struct Directory {
-    directories: HashSet<String>,
-    files: HashMap<String, u32>,
-}
-
-impl Directory {
-    fn new() -> Self {
-        Directory {
-            directories: HashSet::new(),
-            files: HashMap::new(),
-        }
-    }
-}
+mod filesystem;
+mod planner;
+mod transcript;
 
-const MAX_FILE_SIZE: u32 = 100000;
-const TOTAL_DISK_SPACE: u32 = 70000000;
-const REQUIRED_SPACE: u32 = 30000000;
+const MAX_FILE_SIZE: u64 = 100000;
+const TOTAL_DISK_SPACE: u64 = 70000000;
+const REQUIRED_SPACE: u64 = 30000000;
 fn main() {
     let input = read_to_string("input").unwrap();
-    let directories = process_input(&input);
-
-    let output_1 = part_1(&directories);
-    let output_2 = part_2(&directories);
-
-    println!("part 1: {output_1} part 2: {output_2}");
-}
-
-fn part_1(directories: &HashMap<Vec<String>, Directory>) -> u32 {
-    let mut output = 0;
-    for path in directories.keys() {
-        let size = get_size(&directories, path.clone());
-        if size <= MAX_FILE_SIZE {
-            output += size
+    let (file_system, inconsistencies) = match replay(&input) {
+        Ok(replayed) => replayed,
+        Err(error) => {
+            eprintln!("invalid transcript: {error}");
+            return;
         }
-    }
-
-    output
-}
+    };
 
-fn part_2(directories: &HashMap<Vec<String>, Directory>) -> u32 {
-    let min_file_size = REQUIRED_SPACE + get_size(directories, vec![]) - TOTAL_DISK_SPACE;
-    directories
-        .keys()
-        .map(|path| get_size(directories, path.clone()))
-        .filter(|&n| n >= min_file_size)
-        .min()
-        .unwrap()
-}
-
-fn get_size(directories: &HashMap<Vec<String>, Directory>, path: Vec<String>) -> u32 {
-    let mut size = 0;
-    let directory = &directories[&path];
-    size += directory.files.values().sum::<u32>();
-
-    for sub_directory in directory.directories.clone() {
-        let new_path = [path.clone(), vec![sub_directory]].concat();
-        size += get_size(directories, new_path);
+    for inconsistency in inconsistencies {
+        eprintln!("warning: {inconsistency}");
     }
 
-    size
-}
+    let output_1 = part_1(&file_system);
+    let output_2 = part_2(&file_system);
 
-fn process_input(input: &str) -> HashMap<Vec<String>, Directory> {
-    let mut directories: HashMap<Vec<String>, Directory> = HashMap::new();
-    directories.insert(vec![], Directory::new());
-    let mut current_path = vec![];
+    println!("part 1: {output_1} part 2: {output_2}");
 
-    for line in input.lines() {
-        if line == "$ ls" {
-            continue;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args
+        .iter()
+        .map(|s| s.as_str())
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [] => {}
+        ["tree"] => print!("{}", file_system.tree()),
+        ["du", path] => match file_system.du(path) {
+            Some(size) => println!("{size}\t{path}"),
+            None => eprintln!("du: {path}: no such file or directory"),
+        },
+        ["find", name] => {
+            for id in file_system.find(name) {
+                println!("{}", file_system.path(id));
+            }
         }
-        if line.starts_with("$ cd") {
-            let next_directory = line.split_whitespace().last().unwrap();
-            match next_directory {
-                ".." => {
-                    current_path.pop();
-                }
-
-                "/" => {
-                    current_path.clear();
-                }
-
-                next => {
-                    current_path.push(next.to_string());
-                    directories
-                        .entry(current_path.clone())
-                        .or_insert(Directory::new());
-                }
-            };
-            continue;
+        ["largest", count] if count.parse::<usize>().is_ok() => {
+            for id in file_system.largest_directories(count.parse().unwrap()) {
+                println!("{}\t{}", file_system.node(id).size, file_system.path(id));
+            }
         }
+        ["plan", options @ ..] => match get_plan_options(options) {
+            Some(options) => match plan(&file_system, &options) {
+                Some(plan) => println!("{}", plan.explain(&file_system, &options)),
+                None => eprintln!("plan: no set of deletions frees enough space"),
+            },
+            None => eprintln!(
+                "usage: day_7 plan [files] [max <count>] [total <size>] [required <size>]"
+            ),
+        },
+        _ => eprintln!(
+            "usage: day_7 [tree | du <path> | find <name> | largest <count> | plan [options]]"
+        ),
+    }
+}
 
-        if line.starts_with("dir") {
-            let next = line.chars().skip(4).collect();
-            directories
-                .get_mut(&current_path)
-                .unwrap()
-                .directories
-                .insert(next);
-            continue;
+fn get_plan_options(arguments: &[&str]) -> Option<PlanOptions> {
+    let mut options = PlanOptions {
+        total_space: TOTAL_DISK_SPACE,
+        required_space: REQUIRED_SPACE,
+        max_deletions: None,
+        include_files: false,
+    };
+
+    let mut arguments = arguments.iter();
+    while let Some(&argument) = arguments.next() {
+        match argument {
+            "files" => options.include_files = true,
+            "max" => options.max_deletions = Some(arguments.next()?.parse().ok()?),
+            "total" => options.total_space = arguments.next()?.parse().ok()?,
+            "required" => options.required_space = arguments.next()?.parse().ok()?,
+            _ => return None,
         }
+    }
 
-        let file_size = line
-            .chars()
-            .take_while(|&c| c != ' ')
-            .collect::<String>()
-            .parse()
-            .unwrap();
-
-        let file_name = line.chars().skip_while(|&c| c != ' ').skip(1).collect();
+    Some(options)
+}
 
-        directories
-            .get_mut(&current_path)
-            .unwrap()
-            .files
-            .insert(file_name, file_size);
-    }
+fn part_1(file_system: &FileSystem) -> u64 {
+    file_system
+        .directories()
+        .map(|id| file_system.node(id).size)
+        .filter(|&size| size <= MAX_FILE_SIZE)
+        .sum()
+}
 
-    directories
+fn part_2(file_system: &FileSystem) -> u64 {
+    let used_space = file_system.node(FileSystem::ROOT).size;
+    let min_file_size = REQUIRED_SPACE + used_space - TOTAL_DISK_SPACE;
+    file_system
+        .directories()
+        .map(|id| file_system.node(id).size)
+        .filter(|&n| n >= min_file_size)
+        .min()
+        .unwrap()
 }
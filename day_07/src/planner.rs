@@ -0,0 +1,154 @@
+use crate::filesystem::{FileSystem, NodeId};
+
+pub struct PlanOptions {
+    pub total_space: u64,
+    pub required_space: u64,
+    pub max_deletions: Option<usize>,
+    pub include_files: bool,
+}
+
+pub struct Plan {
+    pub used_space: u64,
+    pub needed: u64,
+    pub freed: u64,
+    pub deletions: Vec<NodeId>,
+}
+
+impl Plan {
+    pub fn explain(&self, file_system: &FileSystem, options: &PlanOptions) -> String {
+        let free_space = options.total_space.saturating_sub(self.used_space);
+        let mut lines = vec![format!(
+            "{} of {} used, {free_space} free; {} must be free, so at least {} has to be deleted",
+            self.used_space, options.total_space, options.required_space, self.needed
+        )];
+
+        for &id in &self.deletions {
+            let kind = if file_system.is_directory(id) {
+                "directory"
+            } else {
+                "file"
+            };
+            lines.push(format!(
+                "delete {kind} {} ({})",
+                file_system.path(id),
+                file_system.node(id).size
+            ));
+        }
+
+        lines.push(format!(
+            "{} deletion(s) free {}, {} more than needed, leaving {} free",
+            self.deletions.len(),
+            self.freed,
+            self.freed - self.needed,
+            free_space + self.freed
+        ));
+
+        lines.join("\n")
+    }
+}
+
+struct Candidate {
+    id: NodeId,
+    size: u64,
+    end: usize,
+}
+
+struct Search {
+    candidates: Vec<Candidate>,
+    remaining: Vec<u64>,
+    needed: u64,
+    max_deletions: usize,
+    chosen: Vec<usize>,
+    best: Option<(u64, Vec<usize>)>,
+}
+
+impl Search {
+    fn best_freed(&self) -> Option<u64> {
+        self.best.as_ref().map(|(freed, _)| *freed)
+    }
+
+    fn search(&mut self, index: usize, freed: u64) {
+        if freed >= self.needed {
+            if self.best_freed().is_none_or(|best| freed < best) {
+                self.best = Some((freed, self.chosen.clone()));
+            }
+            return;
+        }
+
+        if index == self.candidates.len()
+            || self.chosen.len() == self.max_deletions
+            || freed + self.remaining[index] < self.needed
+            || self.best_freed() == Some(self.needed)
+        {
+            return;
+        }
+
+        let candidate = &self.candidates[index];
+        let (size, end) = (candidate.size, candidate.end);
+        if size > 0 && self.best_freed().is_none_or(|best| freed + size < best) {
+            self.chosen.push(index);
+            self.search(end, freed + size);
+            self.chosen.pop();
+        }
+
+        self.search(index + 1, freed);
+    }
+}
+
+fn get_candidates(
+    file_system: &FileSystem,
+    id: NodeId,
+    include_files: bool,
+    candidates: &mut Vec<Candidate>,
+) {
+    if !include_files && !file_system.is_directory(id) {
+        return;
+    }
+
+    let index = candidates.len();
+    candidates.push(Candidate {
+        id,
+        size: file_system.node(id).size,
+        end: 0,
+    });
+    for child in file_system.children(id) {
+        get_candidates(file_system, child, include_files, candidates);
+    }
+    candidates[index].end = candidates.len();
+}
+
+pub fn plan(file_system: &FileSystem, options: &PlanOptions) -> Option<Plan> {
+    let used_space = file_system.node(FileSystem::ROOT).size;
+    let needed = (options.required_space + used_space).saturating_sub(options.total_space);
+
+    let mut candidates = vec![];
+    for child in file_system.children(FileSystem::ROOT) {
+        get_candidates(file_system, child, options.include_files, &mut candidates);
+    }
+
+    let mut remaining = vec![0; candidates.len() + 1];
+    for index in (0..candidates.len()).rev() {
+        remaining[index] = candidates[index].size + remaining[candidates[index].end];
+    }
+
+    let mut search = Search {
+        candidates,
+        remaining,
+        needed,
+        max_deletions: options.max_deletions.unwrap_or(usize::MAX),
+        chosen: vec![],
+        best: None,
+    };
+    search.search(0, 0);
+
+    let (freed, chosen) = search.best?;
+    Some(Plan {
+        used_space,
+        needed,
+        freed,
+        deletions: chosen
+            .into_iter()
+            .map(|index| search.candidates[index].id)
+            .collect(),
+    })
+}
@@ -0,0 +1,416 @@
+use crate::filesystem::{FileSystem, NodeId};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub enum TranscriptError {
+    UnknownCommand { line: usize, command: String },
+    MissingArgument { line: usize, command: String },
+    MalformedEntry { line: usize, text: String },
+    OutputWithoutLs { line: usize },
+    NoSuchPath { line: usize, path: String },
+    NotADirectory { line: usize, path: String },
+    AlreadyExists { line: usize, path: String },
+    MoveIntoItself { line: usize, path: String },
+    RemoveRoot { line: usize },
+    InvalidName { line: usize, name: String },
+}
+
+impl Display for TranscriptError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TranscriptError::UnknownCommand { line, command } => {
+                write!(f, "line {line}: unknown command {command:?}")
+            }
+            TranscriptError::MissingArgument { line, command } => {
+                write!(f, "line {line}: {command} is missing an argument")
+            }
+            TranscriptError::MalformedEntry { line, text } => {
+                write!(f, "line {line}: malformed ls entry {text:?}")
+            }
+            TranscriptError::OutputWithoutLs { line } => {
+                write!(f, "line {line}: output outside of an ls listing")
+            }
+            TranscriptError::NoSuchPath { line, path } => {
+                write!(f, "line {line}: {path}: no such file or directory")
+            }
+            TranscriptError::NotADirectory { line, path } => {
+                write!(f, "line {line}: {path}: not a directory")
+            }
+            TranscriptError::AlreadyExists { line, path } => {
+                write!(f, "line {line}: {path}: already exists")
+            }
+            TranscriptError::MoveIntoItself { line, path } => {
+                write!(f, "line {line}: cannot move {path} into itself")
+            }
+            TranscriptError::RemoveRoot { line } => {
+                write!(f, "line {line}: cannot remove the root directory")
+            }
+            TranscriptError::InvalidName { line, name } => {
+                write!(f, "line {line}: {name:?} is not a valid name")
+            }
+        }
+    }
+}
+
+impl Error for TranscriptError {}
+
+#[derive(Debug)]
+pub enum Inconsistency {
+    SizeChanged {
+        line: usize,
+        path: String,
+        previous: u64,
+        listed: u64,
+    },
+    KindChanged {
+        line: usize,
+        path: String,
+    },
+    EnteredBeforeListed {
+        line: usize,
+        path: String,
+    },
+}
+
+impl Display for Inconsistency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Inconsistency::SizeChanged {
+                line,
+                path,
+                previous,
+                listed,
+            } => write!(
+                f,
+                "line {line}: {path} listed with size {listed} but was previously {previous}"
+            ),
+            Inconsistency::KindChanged { line, path } => write!(
+                f,
+                "line {line}: {path} listed as a different kind of entry than before"
+            ),
+            Inconsistency::EnteredBeforeListed { line, path } => {
+                write!(f, "line {line}: entered {path} before it was listed")
+            }
+        }
+    }
+}
+
+struct Replay {
+    file_system: FileSystem,
+    current: NodeId,
+    listing: bool,
+    inconsistencies: Vec<Inconsistency>,
+}
+
+fn split_path(path: &str) -> (&str, &str) {
+    match path.rsplit_once('/') {
+        Some(("", name)) => ("/", name),
+        Some((parent, name)) => (parent, name),
+        None => (".", path),
+    }
+}
+
+fn check_name(line: usize, name: &str) -> Result<(), TranscriptError> {
+    if matches!(name, "" | "." | "..") || name.contains('/') {
+        return Err(TranscriptError::InvalidName {
+            line,
+            name: name.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+impl Replay {
+    fn resolve(&self, line: usize, path: &str) -> Result<NodeId, TranscriptError> {
+        self.file_system
+            .resolve(self.current, path)
+            .ok_or_else(|| TranscriptError::NoSuchPath {
+                line,
+                path: path.to_string(),
+            })
+    }
+
+    fn resolve_directory(&self, line: usize, path: &str) -> Result<NodeId, TranscriptError> {
+        let id = self.resolve(line, path)?;
+        if !self.file_system.is_directory(id) {
+            return Err(TranscriptError::NotADirectory {
+                line,
+                path: path.to_string(),
+            });
+        }
+
+        Ok(id)
+    }
+
+    fn change_directory(&mut self, line: usize, path: &str) -> Result<(), TranscriptError> {
+        let mut current = if path.starts_with('/') {
+            FileSystem::ROOT
+        } else {
+            self.current
+        };
+
+        for segment in path.split('/') {
+            current = match segment {
+                "" | "." => current,
+                ".." => self
+                    .file_system
+                    .node(current)
+                    .parent
+                    .unwrap_or(FileSystem::ROOT),
+                name => match self.file_system.child(current, name) {
+                    Some(id) if self.file_system.is_directory(id) => id,
+                    Some(_) => {
+                        return Err(TranscriptError::NotADirectory {
+                            line,
+                            path: path.to_string(),
+                        })
+                    }
+                    None => {
+                        let id = self.file_system.make_directory(current, name);
+                        self.inconsistencies
+                            .push(Inconsistency::EnteredBeforeListed {
+                                line,
+                                path: self.file_system.path(id),
+                            });
+                        id
+                    }
+                },
+            };
+        }
+
+        self.current = current;
+        Ok(())
+    }
+
+    fn make_directory(&mut self, line: usize, path: &str) -> Result<(), TranscriptError> {
+        let (parent, name) = split_path(path);
+        check_name(line, name)?;
+        let parent = self.resolve_directory(line, parent)?;
+        if self.file_system.child(parent, name).is_some() {
+            return Err(TranscriptError::AlreadyExists {
+                line,
+                path: path.to_string(),
+            });
+        }
+
+        self.file_system.make_directory(parent, name);
+        Ok(())
+    }
+
+    fn remove(&mut self, line: usize, path: &str) -> Result<(), TranscriptError> {
+        let id = self.resolve(line, path)?;
+        if id == FileSystem::ROOT {
+            return Err(TranscriptError::RemoveRoot { line });
+        }
+
+        if self.file_system.is_ancestor(id, self.current) {
+            self.current = self.file_system.node(id).parent.unwrap_or(FileSystem::ROOT);
+        }
+
+        self.file_system.remove(id);
+        Ok(())
+    }
+
+    fn move_node(
+        &mut self,
+        line: usize,
+        source: &str,
+        target: &str,
+    ) -> Result<(), TranscriptError> {
+        let id = self.resolve(line, source)?;
+        let (parent, name) = match self.file_system.resolve(self.current, target) {
+            Some(target_id) if self.file_system.is_directory(target_id) => {
+                (target_id, self.file_system.node(id).name.clone())
+            }
+            Some(_) => {
+                return Err(TranscriptError::AlreadyExists {
+                    line,
+                    path: target.to_string(),
+                })
+            }
+            None => {
+                let (parent, name) = split_path(target);
+                check_name(line, name)?;
+                (self.resolve_directory(line, parent)?, name.to_string())
+            }
+        };
+
+        if self.file_system.is_ancestor(id, parent) {
+            return Err(TranscriptError::MoveIntoItself {
+                line,
+                path: source.to_string(),
+            });
+        }
+
+        if self.file_system.child(parent, &name).is_some() {
+            return Err(TranscriptError::AlreadyExists {
+                line,
+                path: target.to_string(),
+            });
+        }
+
+        self.file_system.move_node(id, parent, &name);
+        Ok(())
+    }
+
+    fn list_directory(&mut self, line: usize, name: &str) {
+        match self.file_system.child(self.current, name) {
+            Some(id) if !self.file_system.is_directory(id) => {
+                self.inconsistencies.push(Inconsistency::KindChanged {
+                    line,
+                    path: self.file_system.path(id),
+                })
+            }
+            _ => {
+                self.file_system.make_directory(self.current, name);
+            }
+        }
+    }
+
+    fn list_file(&mut self, line: usize, name: &str, size: u64) {
+        match self.file_system.child(self.current, name) {
+            None => {
+                self.file_system.add_file(self.current, name, size);
+            }
+            Some(id) if self.file_system.is_directory(id) => {
+                self.inconsistencies.push(Inconsistency::KindChanged {
+                    line,
+                    path: self.file_system.path(id),
+                })
+            }
+            Some(id) => {
+                let previous = self.file_system.node(id).size;
+                if previous != size {
+                    self.inconsistencies.push(Inconsistency::SizeChanged {
+                        line,
+                        path: self.file_system.path(id),
+                        previous,
+                        listed: size,
+                    });
+                    self.file_system.set_file_size(id, size);
+                }
+            }
+        }
+    }
+
+    fn command(&mut self, line: usize, command: &str) -> Result<(), TranscriptError> {
+        let arguments: Vec<&str> = command.split_whitespace().collect();
+        let missing_argument = || TranscriptError::MissingArgument {
+            line,
+            command: command.to_string(),
+        };
+
+        self.listing = false;
+        match arguments.as_slice() {
+            ["ls"] => self.listing = true,
+            ["cd", path] => self.change_directory(line, path)?,
+            ["mkdir", path] => self.make_directory(line, path)?,
+            ["rm", path] => self.remove(line, path)?,
+            ["mv", source, target] => self.move_node(line, source, target)?,
+            ["cd" | "mkdir" | "rm"] | ["mv", ..] => return Err(missing_argument()),
+            _ => {
+                return Err(TranscriptError::UnknownCommand {
+                    line,
+                    command: command.to_string(),
+                })
+            }
+        }
+
+        Ok(())
+    }
+
+    fn entry(&mut self, line: usize, text: &str) -> Result<(), TranscriptError> {
+        if !self.listing {
+            return Err(TranscriptError::OutputWithoutLs { line });
+        }
+
+        let malformed = || TranscriptError::MalformedEntry {
+            line,
+            text: text.to_string(),
+        };
+        let (kind, name) = text.split_once(' ').ok_or_else(malformed)?;
+        check_name(line, name)?;
+
+        match kind {
+            "dir" => self.list_directory(line, name),
+            size => self.list_file(line, name, size.parse().map_err(|_| malformed())?),
+        }
+
+        Ok(())
+    }
+}
+
+pub fn replay(input: &str) -> Result<(FileSystem, Vec<Inconsistency>), TranscriptError> {
+    let mut replay = Replay {
+        file_system: FileSystem::new(),
+        current: FileSystem::ROOT,
+        listing: false,
+        inconsistencies: vec![],
+    };
+
+    for (index, line) in input.lines().enumerate() {
+        match line.strip_prefix("$ ") {
+            Some(command) => replay.command(index + 1, command)?,
+            None => replay.entry(index + 1, line)?,
+        }
+    }
+
+    Ok((replay.file_system, replay.inconsistencies))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid_name(input: &str) -> Option<(usize, String)> {
+        match replay(input) {
+            Err(TranscriptError::InvalidName { line, name }) => Some((line, name)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn rejects_dot_names_in_listings() {
+        assert_eq!(
+            invalid_name("$ cd /\n$ ls\ndir a\ndir ..\n"),
+            Some((4, "..".to_string()))
+        );
+        assert_eq!(
+            invalid_name("$ cd /\n$ ls\n12 .\n"),
+            Some((3, ".".to_string()))
+        );
+        assert_eq!(
+            invalid_name("$ cd /\n$ ls\ndir \n"),
+            Some((3, String::new()))
+        );
+    }
+
+    #[test]
+    fn rejects_dot_names_in_mkdir() {
+        assert_eq!(invalid_name("$ mkdir ..\n"), Some((1, "..".to_string())));
+        assert_eq!(invalid_name("$ mkdir /a/.\n"), Some((1, ".".to_string())));
+        assert_eq!(invalid_name("$ mkdir a/\n"), Some((1, String::new())));
+        assert_eq!(invalid_name("$ mkdir /\n"), Some((1, String::new())));
+    }
+
+    #[test]
+    fn rejects_dot_names_as_move_targets() {
+        assert_eq!(
+            invalid_name("$ mkdir a\n$ mv a b/..\n"),
+            Some((2, "..".to_string()))
+        );
+        assert_eq!(
+            invalid_name("$ mkdir a\n$ mkdir b\n$ mv a /b/c/.\n"),
+            Some((3, ".".to_string()))
+        );
+    }
+
+    #[test]
+    fn still_navigates_with_dot_segments() {
+        let (file_system, _) =
+            replay("$ cd /\n$ ls\ndir a\n$ cd a/../a/.\n$ ls\n5 f\n$ mv f ..\n").unwrap();
+        assert_eq!(file_system.du("/f"), Some(5));
+    }
+}
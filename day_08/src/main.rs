@@ -1,107 +1,281 @@
-use itertools::Itertools;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
 use std::fs::read_to_string;
 
+type Grid = Vec<Vec<Option<u32>>>;
+
 #[derive(Clone, Copy)]
 enum Direction {
     Up,
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    const AXES: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+        Direction::UpLeft,
+        Direction::UpRight,
+        Direction::DownLeft,
+        Direction::DownRight,
+    ];
+
+    fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (1, -1),
+            Direction::DownLeft => (-1, 1),
+            Direction::DownRight => (1, 1),
+        }
+    }
+
+    fn lines(self, height: usize, width: usize) -> impl Iterator<Item = Line> {
+        let (dx, dy) = self.offset();
+        (0..height)
+            .flat_map(move |y| (0..width).map(move |x| (x, y)))
+            .filter(move |&(x, y)| !in_bounds(x as isize + dx, y as isize + dy, height, width))
+            .map(move |(x, y)| Line {
+                position: Some((x, y)),
+                step: (-dx, -dy),
+                height,
+                width,
+            })
+    }
+}
+
+fn in_bounds(x: isize, y: isize, height: usize, width: usize) -> bool {
+    (0..width as isize).contains(&x) && (0..height as isize).contains(&y)
+}
+
+struct Line {
+    position: Option<(usize, usize)>,
+    step: (isize, isize),
+    height: usize,
+    width: usize,
+}
+
+impl Iterator for Line {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (x, y) = self.position?;
+        let (next_x, next_y) = (x as isize + self.step.0, y as isize + self.step.1);
+        self.position = in_bounds(next_x, next_y, self.height, self.width)
+            .then_some((next_x as usize, next_y as usize));
+        Some((x, y))
+    }
 }
 
 fn main() {
     let input = read_to_string("input").unwrap();
-    let grid = process_input(&input);
+    let grid = match process_input(&input) {
+        Ok(grid) => grid,
+        Err(error) => {
+            eprintln!("invalid input: {error}");
+            return;
+        }
+    };
 
-    let output_1 = count_visible(&grid);
-    let output_2 = max_scenic_score(&grid);
+    let output_1 = count_visible(&grid, &Direction::AXES);
+    let output_2 = max_scenic_score(&grid, &Direction::AXES);
 
     println!("part 1: {output_1} part 2: {output_2}");
+    println!(
+        "with diagonals: visible: {} max scenic score: {}",
+        count_visible(&grid, &Direction::ALL),
+        max_scenic_score(&grid, &Direction::ALL)
+    );
 }
 
-fn is_visible_from_direction(
-    grid: &Vec<Vec<u32>>,
-    (x, y): (usize, usize),
-    direction: Direction,
-) -> bool {
-    let values_to_check: Vec<u32> = match direction {
-        Direction::Up => (0..y).map(|i| grid[i][x]).collect(),
-        Direction::Down => (y + 1..grid.len()).map(|i| grid[i][x]).collect(),
-        Direction::Left => (0..x).map(|i| grid[y][i]).collect(),
-        Direction::Right => (x + 1..grid[0].len()).map(|i| grid[y][i]).collect(),
-    };
+fn get_height(grid: &Grid, (x, y): (usize, usize)) -> Option<u32> {
+    grid.get(y).and_then(|row| row.get(x)).copied().flatten()
+}
 
-    values_to_check.iter().all(|&n| n < grid[y][x])
+fn all_lines<'a>(
+    grid: &'a Grid,
+    directions: &'a [Direction],
+) -> impl Iterator<Item = impl Iterator<Item = ((usize, usize), u32)> + 'a> + 'a {
+    let height = grid.len();
+    let width = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+    directions
+        .iter()
+        .flat_map(move |direction| direction.lines(height, width))
+        .map(|line| line.filter_map(|position| Some((position, get_height(grid, position)?))))
 }
 
-fn is_visible(grid: &Vec<Vec<u32>>, pos: (usize, usize)) -> bool {
-    [
-        Direction::Up,
-        Direction::Down,
-        Direction::Left,
-        Direction::Right,
-    ]
-    .iter()
-    .any(|&d| is_visible_from_direction(&grid, pos, d))
+fn visibility_grid(grid: &Grid, directions: &[Direction]) -> Vec<Vec<bool>> {
+    let mut visible: Vec<Vec<bool>> = grid.iter().map(|row| vec![false; row.len()]).collect();
+    for line in all_lines(grid, directions) {
+        let mut tallest = None;
+        for ((x, y), height) in line {
+            if tallest.is_none_or(|tallest| height > tallest) {
+                visible[y][x] = true;
+                tallest = Some(height);
+            }
+        }
+    }
+
+    visible
 }
 
-fn count_visible(grid: &Vec<Vec<u32>>) -> usize {
-    (0..grid.len())
-        .cartesian_product(0..grid[0].len())
-        .filter(|&pos| is_visible(grid, pos))
+fn scenic_grid(grid: &Grid, directions: &[Direction]) -> Vec<Vec<usize>> {
+    let mut scores: Vec<Vec<usize>> = grid.iter().map(|row| vec![1; row.len()]).collect();
+    for line in all_lines(grid, directions) {
+        let mut blockers: Vec<(usize, u32)> = vec![];
+        for (i, ((x, y), height)) in line.enumerate() {
+            while blockers.last().is_some_and(|&(_, h)| h < height) {
+                blockers.pop();
+            }
+
+            scores[y][x] *= i - blockers.last().map_or(0, |&(j, _)| j);
+            blockers.push((i, height));
+        }
+    }
+
+    scores
+}
+
+fn count_visible(grid: &Grid, directions: &[Direction]) -> usize {
+    visibility_grid(grid, directions)
+        .into_iter()
+        .flatten()
+        .filter(|&visible| visible)
         .count()
 }
 
-fn process_input(input: &str) -> Vec<Vec<u32>> {
+fn max_scenic_score(grid: &Grid, directions: &[Direction]) -> usize {
+    let scores = scenic_grid(grid, directions);
+    grid.iter()
+        .zip(scores)
+        .flat_map(|(row, scores)| row.iter().zip(scores))
+        .filter(|(height, _)| height.is_some())
+        .map(|(_, score)| score)
+        .max()
+        .unwrap_or(0)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ParseError {
+    line: usize,
+    column: usize,
+    cell: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: invalid cell {:?}",
+            self.line, self.column, self.cell
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+fn parse_cell(cell: &str, line: usize, column: usize) -> Result<Option<u32>, ParseError> {
+    match cell {
+        "." => Ok(None),
+        cell => cell.parse().map(Some).map_err(|_| ParseError {
+            line,
+            column,
+            cell: cell.to_string(),
+        }),
+    }
+}
+
+fn process_input(input: &str) -> Result<Grid, ParseError> {
+    let is_separated = input
+        .lines()
+        .any(|line| line.trim().contains(char::is_whitespace));
+
     input
         .lines()
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
+        .enumerate()
+        .map(|(index, line)| {
+            if is_separated {
+                line.split_whitespace()
+                    .map(|cell| {
+                        let offset = cell.as_ptr() as usize - line.as_ptr() as usize;
+                        parse_cell(cell, index + 1, line[..offset].chars().count() + 1)
+                    })
+                    .collect()
+            } else {
+                line.char_indices()
+                    .enumerate()
+                    .map(|(column, (offset, c))| {
+                        parse_cell(&line[offset..offset + c.len_utf8()], index + 1, column + 1)
+                    })
+                    .collect()
+            }
+        })
         .collect()
 }
 
-fn viewing_distance(grid: &Vec<Vec<u32>>, (x, y): (usize, usize), direction: Direction) -> usize {
-    let values_to_check: Vec<u32> = match direction {
-        Direction::Up => (0..y).map(|i| grid[i][x]).rev().collect(),
-        Direction::Down => (y + 1..grid.len()).map(|i| grid[i][x]).collect(),
-        Direction::Left => (0..x).map(|i| grid[y][i]).rev().collect(),
-        Direction::Right => (x + 1..grid[0].len()).map(|i| grid[y][i]).collect(),
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if values_to_check.len() == 0 {
-        return 0;
-    };
+    #[test]
+    fn detects_separated_input_from_any_line() {
+        assert_eq!(
+            process_input("12\n3 4\n. 56\n"),
+            Ok(vec![
+                vec![Some(12)],
+                vec![Some(3), Some(4)],
+                vec![None, Some(56)]
+            ])
+        );
+    }
 
-    values_to_check
-        .into_iter()
-        .scan(true, |visible, n| {
-            if !*visible {
-                return Some((n, false));
-            };
-            if n >= grid[y][x] {
-                *visible = false
-            };
-            Some((n, true))
-        })
-        .take_while(|&(_, b)| b)
-        .count()
-}
+    #[test]
+    fn parses_digit_rows() {
+        assert_eq!(
+            process_input("30.\n25\n"),
+            Ok(vec![vec![Some(3), Some(0), None], vec![Some(2), Some(5)]])
+        );
+    }
 
-fn scenic_score(grid: &Vec<Vec<u32>>, pos: (usize, usize)) -> usize {
-    [
-        Direction::Up,
-        Direction::Down,
-        Direction::Left,
-        Direction::Right,
-    ]
-    .into_iter()
-    .map(|d| viewing_distance(grid, pos, d))
-    .product()
-}
+    #[test]
+    fn reports_invalid_digits() {
+        assert_eq!(
+            process_input("303\n2x5\n"),
+            Err(ParseError {
+                line: 2,
+                column: 2,
+                cell: "x".to_string()
+            })
+        );
+    }
 
-fn max_scenic_score(grid: &Vec<Vec<u32>>) -> usize {
-    (0..grid.len())
-        .cartesian_product(0..grid[0].len())
-        .map(|pos| scenic_score(grid, pos))
-        .max()
-        .unwrap()
+    #[test]
+    fn reports_invalid_separated_cells() {
+        assert_eq!(
+            process_input("1 2\n3  -4 5\n"),
+            Err(ParseError {
+                line: 2,
+                column: 4,
+                cell: "-4".to_string()
+            })
+        );
+    }
 }
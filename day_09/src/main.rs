@@ -1,67 +1,57 @@
 use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
 use std::fs::read_to_string;
 
 #[derive(Copy, Clone)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+struct Direction<const D: usize> {
+    offset: [i32; D],
 }
 
 #[derive(Hash, Eq, PartialEq, Copy, Clone, Debug)]
-struct Position {
-    x: i32,
-    y: i32,
+struct Position<const D: usize> {
+    coordinates: [i32; D],
 }
 
-impl Position {
+impl<const D: usize> Position<D> {
     fn new() -> Self {
-        Position { x: 0, y: 0 }
+        Position {
+            coordinates: [0; D],
+        }
     }
 
-    fn step(&mut self, direction: Direction) {
-        let (dx, dy) = match direction {
-            Direction::Up => (0, -1),
-            Direction::Down => (0, 1),
-            Direction::Left => (-1, 0),
-            Direction::Right => (1, 0),
-        };
-
-        self.x += dx;
-        self.y += dy;
+    fn step(&mut self, direction: Direction<D>) {
+        for (coordinate, delta) in self.coordinates.iter_mut().zip(direction.offset) {
+            *coordinate += delta;
+        }
     }
 
     fn pull(&mut self, next: Self) {
-        let dx = next.x - self.x;
-        let dy = next.y - self.y;
+        let deltas: [i32; D] = std::array::from_fn(|i| next.coordinates[i] - self.coordinates[i]);
 
-        if dx.abs() <= 1 && dy.abs() <= 1 {
+        if deltas.iter().all(|delta| delta.abs() <= 1) {
             return;
         }
 
-        if dx > 0 {
-            self.x += 1
+        for (coordinate, delta) in self.coordinates.iter_mut().zip(deltas) {
+            *coordinate += delta.signum();
         }
-        if dx < 0 {
-            self.x -= 1
-        }
-        if dy > 0 {
-            self.y += 1
-        }
-        if dy < 0 {
-            self.y -= 1
-        }1
+    }
+}
+
+impl<const D: usize> Display for Position<D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let coordinates: Vec<String> = self.coordinates.iter().map(|c| c.to_string()).collect();
+        write!(f, "({})", coordinates.join(", "))
     }
 }
 
 #[derive(Debug, Clone)]
-struct Rope {
-    knots: Vec<Position>,
+struct Rope<const D: usize> {
+    knots: Vec<Position<D>>,
 }
 
-impl Rope {
-    fn step(&mut self, direction: Direction) {
+impl<const D: usize> Rope<D> {
+    fn step(&mut self, direction: Direction<D>) {
         self.knots.get_mut(0).unwrap().step(direction);
         for i in 0..(self.knots.len() - 1) {
             let next = self.knots[i];
@@ -77,51 +67,180 @@ impl Rope {
     }
 }
 
+#[derive(Clone, Copy, Debug)]
+struct BoundingBox<const D: usize> {
+    min: Position<D>,
+    max: Position<D>,
+}
+
+impl<const D: usize> BoundingBox<D> {
+    fn around(positions: &[Position<D>]) -> Self {
+        positions.iter().fold(
+            BoundingBox {
+                min: positions[0],
+                max: positions[0],
+            },
+            |bounding_box, &position| bounding_box.extend(position),
+        )
+    }
+
+    fn extend(self, position: Position<D>) -> Self {
+        BoundingBox {
+            min: Position {
+                coordinates: std::array::from_fn(|i| {
+                    self.min.coordinates[i].min(position.coordinates[i])
+                }),
+            },
+            max: Position {
+                coordinates: std::array::from_fn(|i| {
+                    self.max.coordinates[i].max(position.coordinates[i])
+                }),
+            },
+        }
+    }
+
+    fn union(self, other: Self) -> Self {
+        self.extend(other.min).extend(other.max)
+    }
+
+    fn spread(&self) -> i32 {
+        (0..D)
+            .map(|i| self.max.coordinates[i] - self.min.coordinates[i])
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+struct Simulation<const D: usize> {
+    rope: Rope<D>,
+    step_count: usize,
+    visited: Vec<HashSet<Position<D>>>,
+    first_moves: Vec<Option<usize>>,
+    bounds_history: Vec<BoundingBox<D>>,
+}
+
+impl<const D: usize> Simulation<D> {
+    fn new(length: usize) -> Self {
+        let rope = Rope::new(length);
+        Simulation {
+            step_count: 0,
+            visited: rope.knots.iter().map(|&p| HashSet::from([p])).collect(),
+            first_moves: vec![None; length],
+            bounds_history: vec![BoundingBox::around(&rope.knots)],
+            rope,
+        }
+    }
+
+    fn step(&mut self, direction: Direction<D>) {
+        let previous = self.rope.knots.clone();
+        self.rope.step(direction);
+        self.step_count += 1;
+
+        for (i, (&knot, &previous)) in self.rope.knots.iter().zip(&previous).enumerate() {
+            if knot != previous && self.first_moves[i].is_none() {
+                self.first_moves[i] = Some(self.step_count);
+            }
+            self.visited[i].insert(knot);
+        }
+        self.bounds_history
+            .push(BoundingBox::around(&self.rope.knots));
+    }
+
+    fn run(&mut self, instructions: &[(Direction<D>, u32)]) {
+        for &(direction, step_count) in instructions {
+            for _ in 0..step_count {
+                self.step(direction);
+            }
+        }
+    }
+
+    fn visited_by_tail(&self, rope_length: usize) -> usize {
+        self.visited[rope_length - 1].len()
+    }
+
+    fn max_spread(&self) -> i32 {
+        self.bounds_history
+            .iter()
+            .map(|bounding_box| bounding_box.spread())
+            .max()
+            .unwrap()
+    }
+
+    fn total_bounds(&self) -> BoundingBox<D> {
+        self.bounds_history
+            .iter()
+            .copied()
+            .reduce(BoundingBox::union)
+            .unwrap()
+    }
+}
+
 fn main() {
     let input = read_to_string("input").unwrap();
-    let instructions = process_input(&input);
+    if input.contains(['F', 'B']) {
+        report::<3>(&input);
+    } else {
+        report::<2>(&input);
+    }
+}
+
+fn report<const D: usize>(input: &str) {
+    let instructions = process_input::<D>(input);
+
+    let mut simulation = Simulation::new(10);
+    simulation.run(&instructions);
 
-    let output_1 = get_visited(&instructions, 2);
-    let output_2 = get_visited(&instructions, 10);
+    let output_1 = simulation.visited_by_tail(2);
+    let output_2 = simulation.visited_by_tail(10);
 
     println!("part 1: {output_1} part 2: {output_2}");
+
+    for length in 2..=10 {
+        println!(
+            "length {length}: tail visited {} positions, first moved at step {:?}",
+            simulation.visited_by_tail(length),
+            simulation.first_moves[length - 1]
+        );
+    }
+
+    let bounds = simulation.total_bounds();
+    println!(
+        "max spread: {} bounds: {} to {}",
+        simulation.max_spread(),
+        bounds.min,
+        bounds.max
+    );
 }
 
-fn get_visited(instructions: &Vec<(Direction, u32)>, rope_length: usize) -> usize {
-    let mut visited: HashSet<Position> = HashSet::new();
-    let mut rope = Rope::new(rope_length);
+fn get_direction<const D: usize>(input: &str) -> Direction<D> {
+    let mut offset = [0; D];
+    for c in input.chars() {
+        let (axis, delta) = match c {
+            'R' => (0, 1),
+            'L' => (0, -1),
+            'U' => (1, -1),
+            'D' => (1, 1),
+            'F' => (2, 1),
+            'B' => (2, -1),
+            c => panic!("invalid character: {c}"),
+        };
 
-    for &(direction, step_count) in instructions {
-        for _ in 0..step_count {
-            rope.step(direction);
-            visited.insert(*rope.knots.last().unwrap());
+        if axis >= D {
+            panic!("{c} needs a rope with at least {} dimensions", axis + 1);
+        }
+        if offset[axis] != 0 {
+            panic!("conflicting direction: {input}");
         }
+        offset[axis] = delta;
     }
 
-    visited.len()
+    Direction { offset }
 }
-fn process_input(input: &str) -> Vec<(Direction, u32)> {
+
+fn process_input<const D: usize>(input: &str) -> Vec<(Direction<D>, u32)> {
     input
         .lines()
-        .map(|line| {
-            (
-                line.chars().next().unwrap(),
-                line.chars()
-                    .skip_while(|&c| c != ' ')
-                    .skip(1)
-                    .collect::<String>(),
-            )
-        })
-        .map(|(c, s)| {
-            let direction = match c {
-                'U' => Direction::Up,
-                'D' => Direction::Down,
-                'L' => Direction::Left,
-                'R' => Direction::Right,
-                c => panic!("invalid character: {c}"),
-            };
-            let step_count: u32 = s.parse().unwrap();
-            (direction, step_count)
-        })
+        .map(|line| line.split_once(' ').unwrap())
+        .map(|(direction, step_count)| (get_direction(direction), step_count.parse().unwrap()))
         .collect()
 }
@@ -0,0 +1,33 @@
+; Draws a bracket on the 40x6 CRT: solid top and bottom rows joined by a
+; two pixel bar down the left edge. The sprite starts at x=1.
+
+.const STEP 2
+.const RETURN -38
+.const OFFSCREEN 100
+.const BACK -100
+
+.macro pad cycles
+.repeat cycles
+    noop
+.endr
+.endm
+
+.macro draw_row
+.repeat 19
+    addx STEP
+.endr
+    addx RETURN
+.endm
+
+.macro bar_row
+    addx OFFSCREEN
+    pad 36
+    addx BACK
+.endm
+
+    draw_row
+.repeat 4
+    bar_row
+.endr
+    draw_row
+
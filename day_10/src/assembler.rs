@@ -0,0 +1,587 @@
+use crate::cpu::{Instruction, Register};
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+const MAX_EXPANSION_DEPTH: usize = 64;
+const MAX_EXPANDED_LINES: usize = 1 << 20;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AssembleErrorKind {
+    UnknownInstruction(String),
+    InvalidOperand(String),
+    InvalidRegister(String),
+    UndefinedSymbol(String),
+    DuplicateSymbol(String),
+    MissingArgument(String),
+    WrongArgumentCount {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    Unterminated(&'static str),
+    Unexpected(String),
+    ExpansionTooDeep(String),
+    NegativeRepeatCount(i64),
+    ExpansionTooLarge,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct AssembleError {
+    pub line: usize,
+    pub kind: AssembleErrorKind,
+}
+
+impl Display for AssembleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            AssembleErrorKind::UnknownInstruction(name) => {
+                write!(f, "unknown instruction or macro {name:?}")
+            }
+            AssembleErrorKind::InvalidOperand(operand) => write!(f, "invalid operand {operand:?}"),
+            AssembleErrorKind::InvalidRegister(register) => {
+                write!(f, "invalid register {register:?}")
+            }
+            AssembleErrorKind::UndefinedSymbol(symbol) => write!(f, "undefined symbol {symbol:?}"),
+            AssembleErrorKind::DuplicateSymbol(symbol) => {
+                write!(f, "{symbol:?} is already defined")
+            }
+            AssembleErrorKind::MissingArgument(directive) => {
+                write!(f, "{directive} is missing an argument")
+            }
+            AssembleErrorKind::WrongArgumentCount {
+                name,
+                expected,
+                found,
+            } => write!(f, "{name} takes {expected} arguments, found {found}"),
+            AssembleErrorKind::Unterminated(directive) => {
+                write!(f, "{directive} block is never closed")
+            }
+            AssembleErrorKind::Unexpected(word) => write!(f, "unexpected {word}"),
+            AssembleErrorKind::ExpansionTooDeep(name) => {
+                write!(f, "expansion of {name} nests too deeply")
+            }
+            AssembleErrorKind::NegativeRepeatCount(count) => {
+                write!(f, "cannot repeat a block {count} times")
+            }
+            AssembleErrorKind::ExpansionTooLarge => {
+                write!(f, "program expands to more than {MAX_EXPANDED_LINES} lines")
+            }
+        }
+    }
+}
+
+impl Error for AssembleError {}
+
+#[derive(Clone)]
+struct Line {
+    number: usize,
+    words: Vec<String>,
+}
+
+impl Line {
+    fn error(&self, kind: AssembleErrorKind) -> AssembleError {
+        AssembleError {
+            line: self.number,
+            kind,
+        }
+    }
+}
+
+struct Macro {
+    parameters: Vec<String>,
+    body: Vec<Line>,
+}
+
+#[derive(Default)]
+struct Assembler {
+    constants: HashMap<String, i64>,
+    macros: HashMap<String, Macro>,
+    labels: HashMap<String, usize>,
+    expansions: usize,
+}
+
+fn tokenize(source: &str) -> Vec<Line> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(index, text)| {
+            let text = text.split(';').next().unwrap();
+            let mut words = vec![];
+            for word in text.split_whitespace() {
+                match word.split_once(':') {
+                    Some((label, rest)) if !label.is_empty() => {
+                        words.push(format!("{label}:"));
+                        if !rest.is_empty() {
+                            words.push(rest.to_string());
+                        }
+                    }
+                    _ => words.push(word.to_string()),
+                }
+            }
+
+            (!words.is_empty()).then_some(Line {
+                number: index + 1,
+                words,
+            })
+        })
+        .collect()
+}
+
+fn block_end(lines: &[Line], open: &str, close: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (index, line) in lines.iter().enumerate() {
+        if line.words[0] == open {
+            depth += 1;
+        } else if line.words[0] == close {
+            if depth == 0 {
+                return Some(index);
+            }
+            depth -= 1;
+        }
+    }
+
+    None
+}
+
+fn label_name(word: &str) -> Option<&str> {
+    word.strip_suffix(':')
+}
+
+impl Assembler {
+    fn value(&self, line: &Line, word: &str) -> Result<i64, AssembleError> {
+        if let Ok(value) = word.parse() {
+            return Ok(value);
+        }
+
+        self.constants
+            .get(word)
+            .copied()
+            .ok_or_else(|| line.error(AssembleErrorKind::UndefinedSymbol(word.to_string())))
+    }
+
+    fn define(&self, line: &Line, name: &str) -> Result<(), AssembleError> {
+        let is_identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_identifier || name.parse::<Register>().is_ok() {
+            return Err(line.error(AssembleErrorKind::InvalidOperand(name.to_string())));
+        }
+
+        if self.constants.contains_key(name) || self.macros.contains_key(name) {
+            return Err(line.error(AssembleErrorKind::DuplicateSymbol(name.to_string())));
+        }
+
+        Ok(())
+    }
+
+    fn collect_definitions(&mut self, lines: &[Line]) -> Result<Vec<Line>, AssembleError> {
+        let mut body = vec![];
+        let mut index = 0;
+        while let Some(line) = lines.get(index) {
+            index += 1;
+            match line.words.as_slice() {
+                [directive, name, value] if directive == ".const" => {
+                    self.define(line, name)?;
+                    let value = self.value(line, value)?;
+                    self.constants.insert(name.clone(), value);
+                }
+                [directive, name, parameters @ ..] if directive == ".macro" => {
+                    self.define(line, name)?;
+                    let end = block_end(&lines[index..], ".macro", ".endm")
+                        .ok_or_else(|| line.error(AssembleErrorKind::Unterminated(".macro")))?;
+                    self.macros.insert(
+                        name.clone(),
+                        Macro {
+                            parameters: parameters.to_vec(),
+                            body: lines[index..index + end].to_vec(),
+                        },
+                    );
+                    index += end + 1;
+                }
+                [directive, ..] if directive == ".const" || directive == ".macro" => {
+                    return Err(line.error(AssembleErrorKind::MissingArgument(directive.clone())))
+                }
+                _ => body.push(line.clone()),
+            }
+        }
+
+        Ok(body)
+    }
+
+    fn expand_macro(&mut self, line: &Line, name: &str) -> Vec<Line> {
+        let definition = &self.macros[name];
+        let arguments = &line.words[1..];
+        let local_labels: BTreeSet<&str> = definition
+            .body
+            .iter()
+            .filter_map(|line| label_name(&line.words[0]))
+            .collect();
+
+        self.expansions += 1;
+        let suffix = format!(".{}", self.expansions);
+        definition
+            .body
+            .iter()
+            .map(|body_line| Line {
+                number: line.number,
+                words: body_line
+                    .words
+                    .iter()
+                    .map(|word| {
+                        if let Some(position) = definition.parameters.iter().position(|p| p == word)
+                        {
+                            arguments[position].clone()
+                        } else if let Some(label) = label_name(word) {
+                            format!("{label}{suffix}:")
+                        } else if local_labels.contains(word.as_str()) {
+                            format!("{word}{suffix}")
+                        } else {
+                            word.clone()
+                        }
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    fn expand(
+        &mut self,
+        lines: &[Line],
+        depth: usize,
+        output: &mut Vec<Line>,
+    ) -> Result<(), AssembleError> {
+        let mut index = 0;
+        while let Some(line) = lines.get(index) {
+            index += 1;
+            let name = line.words[0].as_str();
+            if depth > MAX_EXPANSION_DEPTH {
+                return Err(line.error(AssembleErrorKind::ExpansionTooDeep(name.to_string())));
+            }
+
+            match line.words.as_slice() {
+                [directive, count] if directive == ".repeat" => {
+                    let count = self.value(line, count)?;
+                    if count < 0 {
+                        return Err(line.error(AssembleErrorKind::NegativeRepeatCount(count)));
+                    }
+                    let end = block_end(&lines[index..], ".repeat", ".endr")
+                        .ok_or_else(|| line.error(AssembleErrorKind::Unterminated(".repeat")))?;
+                    let body = &lines[index..index + end];
+                    for _ in 0..count {
+                        let length = output.len();
+                        self.expand(body, depth + 1, output)?;
+                        if output.len() == length {
+                            break;
+                        }
+                    }
+                    index += end + 1;
+                }
+                [directive, ..] if directive == ".repeat" => {
+                    return Err(line.error(AssembleErrorKind::MissingArgument(directive.clone())))
+                }
+                [directive, ..] if directive.starts_with('.') => {
+                    return Err(line.error(AssembleErrorKind::Unexpected(directive.clone())))
+                }
+                [_, arguments @ ..] if self.macros.contains_key(name) => {
+                    let expected = self.macros[name].parameters.len();
+                    if arguments.len() != expected {
+                        return Err(line.error(AssembleErrorKind::WrongArgumentCount {
+                            name: name.to_string(),
+                            expected,
+                            found: arguments.len(),
+                        }));
+                    }
+
+                    let expansion = self.expand_macro(line, name);
+                    self.expand(&expansion, depth + 1, output)?;
+                }
+                _ if output.len() == MAX_EXPANDED_LINES => {
+                    return Err(line.error(AssembleErrorKind::ExpansionTooLarge))
+                }
+                _ => output.push(line.clone()),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn assign_labels(&mut self, lines: &[Line]) -> Result<Vec<Line>, AssembleError> {
+        let mut statements = vec![];
+        for line in lines {
+            let mut words = line.words.as_slice();
+            while let Some(label) = words.first().and_then(|word| label_name(word)) {
+                let is_defined = self.constants.contains_key(label)
+                    || self.macros.contains_key(label)
+                    || self.labels.contains_key(label);
+                if is_defined {
+                    return Err(line.error(AssembleErrorKind::DuplicateSymbol(label.to_string())));
+                }
+                self.labels.insert(label.to_string(), statements.len());
+                words = &words[1..];
+            }
+
+            if !words.is_empty() {
+                statements.push(Line {
+                    number: line.number,
+                    words: words.to_vec(),
+                });
+            }
+        }
+
+        Ok(statements)
+    }
+
+    fn register(&self, line: &Line, word: &str) -> Result<Register, AssembleError> {
+        word.parse()
+            .map_err(|_| line.error(AssembleErrorKind::InvalidRegister(word.to_string())))
+    }
+
+    fn offset(&self, line: &Line, address: usize, word: &str) -> Result<i64, AssembleError> {
+        match self.labels.get(word) {
+            Some(&target) => Ok(target as i64 - address as i64),
+            None => self.value(line, word),
+        }
+    }
+
+    fn instruction(&self, line: &Line, address: usize) -> Result<Instruction, AssembleError> {
+        let words: Vec<&str> = line.words.iter().map(|word| word.as_str()).collect();
+        let instruction = match words.as_slice() {
+            ["noop"] => Instruction::Noop,
+            ["addx", value] => Instruction::AddX(self.value(line, value)?),
+            ["addi", register, value] => {
+                Instruction::AddI(self.register(line, register)?, self.value(line, value)?)
+            }
+            ["mul", register, value] => {
+                Instruction::Mul(self.register(line, register)?, self.value(line, value)?)
+            }
+            ["jmp", target] => Instruction::Jmp(self.offset(line, address, target)?),
+            ["jnz", register, target] => Instruction::Jnz(
+                self.register(line, register)?,
+                self.offset(line, address, target)?,
+            ),
+            ["noop" | "addx" | "addi" | "mul" | "jmp" | "jnz", ..] => {
+                return Err(line.error(AssembleErrorKind::InvalidOperand(line.words.join(" "))))
+            }
+            [name, ..] => {
+                return Err(line.error(AssembleErrorKind::UnknownInstruction(name.to_string())))
+            }
+            [] => unreachable!(),
+        };
+
+        Ok(instruction)
+    }
+}
+
+pub fn assemble(source: &str) -> Result<Vec<Instruction>, AssembleError> {
+    let mut assembler = Assembler::default();
+    let body = assembler.collect_definitions(&tokenize(source))?;
+    let mut expanded = vec![];
+    assembler.expand(&body, 0, &mut expanded)?;
+    let statements = assembler.assign_labels(&expanded)?;
+
+    statements
+        .iter()
+        .enumerate()
+        .map(|(address, line)| assembler.instruction(line, address))
+        .collect()
+}
+
+fn jump_target(address: usize, instruction: Instruction, length: usize) -> Option<usize> {
+    match instruction {
+        Instruction::Jmp(offset) | Instruction::Jnz(_, offset) => address
+            .checked_add_signed(offset as isize)
+            .filter(|&target| target <= length),
+        _ => None,
+    }
+}
+
+pub fn disassemble(program: &[Instruction]) -> String {
+    let targets: BTreeSet<usize> = program
+        .iter()
+        .enumerate()
+        .filter_map(|(address, &instruction)| jump_target(address, instruction, program.len()))
+        .collect();
+    let labels: HashMap<usize, String> = targets
+        .iter()
+        .enumerate()
+        .map(|(index, &target)| (target, format!("label_{index}")))
+        .collect();
+
+    let mut output = String::new();
+    let mut cycle = 1;
+    for (address, &instruction) in program.iter().enumerate() {
+        if let Some(label) = labels.get(&address) {
+            output.push_str(&format!("{label}:\n"));
+        }
+
+        let text = match (
+            instruction,
+            jump_target(address, instruction, program.len()),
+        ) {
+            (Instruction::Jmp(_), Some(target)) => format!("jmp {}", labels[&target]),
+            (Instruction::Jnz(register, _), Some(target)) => {
+                format!("jnz {} {}", register.name(), labels[&target])
+            }
+            _ => instruction.to_string(),
+        };
+        output.push_str(&format!("    {text:<24}; {address:>4}  cycle {cycle}\n"));
+        cycle += instruction.cycles();
+    }
+
+    if let Some(label) = labels.get(&program.len()) {
+        output.push_str(&format!("{label}:\n"));
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_kind(source: &str) -> AssembleErrorKind {
+        assemble(source).unwrap_err().kind
+    }
+
+    #[test]
+    fn resolves_labels_in_a_jnz_loop() {
+        let source = "
+            addi y 3
+        loop:
+            addx 1
+            addi y -1
+            jnz y loop
+            jmp end
+            noop
+        end:
+        ";
+        assert_eq!(
+            assemble(source),
+            Ok(vec![
+                Instruction::AddI(Register::Y, 3),
+                Instruction::AddX(1),
+                Instruction::AddI(Register::Y, -1),
+                Instruction::Jnz(Register::Y, -2),
+                Instruction::Jmp(2),
+                Instruction::Noop,
+            ])
+        );
+    }
+
+    #[test]
+    fn substitutes_constants() {
+        let source = "
+            .const step 4
+            .const copy step ; constants can refer to earlier ones
+            addx step
+            mul z copy
+            jmp step
+        ";
+        assert_eq!(
+            assemble(source),
+            Ok(vec![
+                Instruction::AddX(4),
+                Instruction::Mul(Register::Z, 4),
+                Instruction::Jmp(4),
+            ])
+        );
+        assert_eq!(
+            error_kind("addx missing"),
+            AssembleErrorKind::UndefinedSymbol("missing".to_string())
+        );
+    }
+
+    #[test]
+    fn expands_macros_with_local_labels() {
+        let source = "
+            .macro countdown r n
+                addi r n
+            again:
+                addi r -1
+                jnz r again
+            .endm
+            countdown z 2
+            countdown w 5
+        ";
+        assert_eq!(
+            assemble(source),
+            Ok(vec![
+                Instruction::AddI(Register::Z, 2),
+                Instruction::AddI(Register::Z, -1),
+                Instruction::Jnz(Register::Z, -1),
+                Instruction::AddI(Register::W, 5),
+                Instruction::AddI(Register::W, -1),
+                Instruction::Jnz(Register::W, -1),
+            ])
+        );
+        assert_eq!(
+            error_kind(".macro twice r\nmul r 2\n.endm\ntwice"),
+            AssembleErrorKind::WrongArgumentCount {
+                name: "twice".to_string(),
+                expected: 1,
+                found: 0
+            }
+        );
+    }
+
+    #[test]
+    fn repeats_blocks() {
+        let source = "
+            .const times 2
+            .repeat times
+                noop
+                .repeat 0
+                    addx 9
+                .endr
+                addx 1
+            .endr
+        ";
+        assert_eq!(
+            assemble(source),
+            Ok(vec![
+                Instruction::Noop,
+                Instruction::AddX(1),
+                Instruction::Noop,
+                Instruction::AddX(1),
+            ])
+        );
+        assert_eq!(
+            error_kind(".repeat -1\nnoop\n.endr"),
+            AssembleErrorKind::NegativeRepeatCount(-1)
+        );
+    }
+
+    #[test]
+    fn caps_the_size_of_an_expansion() {
+        let source = format!(".repeat {}\nnoop\n.endr\n", MAX_EXPANDED_LINES);
+        assert_eq!(
+            assemble(&source).map(|program| program.len()),
+            Ok(MAX_EXPANDED_LINES)
+        );
+        assert_eq!(
+            error_kind(&format!("{source}noop")),
+            AssembleErrorKind::ExpansionTooLarge
+        );
+        assert_eq!(
+            error_kind(".macro forever\nforever\n.endm\nforever"),
+            AssembleErrorKind::ExpansionTooDeep("forever".to_string())
+        );
+    }
+
+    #[test]
+    fn disassembly_assembles_back_to_the_program() {
+        let program = vec![
+            Instruction::AddI(Register::W, 2),
+            Instruction::AddX(-3),
+            Instruction::Mul(Register::X, 5),
+            Instruction::AddI(Register::W, -1),
+            Instruction::Jnz(Register::W, -3),
+            Instruction::Jmp(2),
+            Instruction::Noop,
+            Instruction::Jmp(-7),
+            Instruction::Jmp(40),
+        ];
+        assert_eq!(assemble(&disassemble(&program)), Ok(program));
+    }
+}
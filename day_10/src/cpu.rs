@@ -0,0 +1,379 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+const CYCLE_LIMIT: usize = 1_000_000;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CycleLimitExceeded {
+    pub limit: usize,
+}
+
+impl Display for CycleLimitExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "program did not halt within {} cycles", self.limit)
+    }
+}
+
+impl Error for CycleLimitExceeded {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Register {
+    X,
+    Y,
+    Z,
+    W,
+}
+
+impl Register {
+    pub const ALL: [Register; 4] = [Register::X, Register::Y, Register::Z, Register::W];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn name(self) -> char {
+        match self {
+            Register::X => 'x',
+            Register::Y => 'y',
+            Register::Z => 'z',
+            Register::W => 'w',
+        }
+    }
+}
+
+impl FromStr for Register {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Register::ALL
+            .into_iter()
+            .find(|register| s.len() == 1 && s.starts_with(register.name()))
+            .ok_or_else(|| format!("unknown register {s:?}"))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    Noop,
+    AddX(i64),
+    AddI(Register, i64),
+    Mul(Register, i64),
+    Jmp(i64),
+    Jnz(Register, i64),
+}
+
+impl Instruction {
+    pub fn cycles(self) -> usize {
+        match self {
+            Instruction::Noop => 1,
+            Instruction::AddX(_) | Instruction::AddI(..) => 2,
+            Instruction::Mul(..) => 3,
+            Instruction::Jmp(_) | Instruction::Jnz(..) => 2,
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_value = |value: &str| {
+            value
+                .parse::<i64>()
+                .map_err(|_| format!("invalid value {value:?} in {s:?}"))
+        };
+
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match words.as_slice() {
+            ["noop"] => Ok(Instruction::Noop),
+            ["addx", value] => Ok(Instruction::AddX(parse_value(value)?)),
+            ["addi", register, value] => {
+                Ok(Instruction::AddI(register.parse()?, parse_value(value)?))
+            }
+            ["mul", register, value] => {
+                Ok(Instruction::Mul(register.parse()?, parse_value(value)?))
+            }
+            ["jmp", offset] => Ok(Instruction::Jmp(parse_value(offset)?)),
+            ["jnz", register, offset] => {
+                Ok(Instruction::Jnz(register.parse()?, parse_value(offset)?))
+            }
+            _ => Err(format!("invalid instruction {s:?}")),
+        }
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instruction::Noop => write!(f, "noop"),
+            Instruction::AddX(value) => write!(f, "addx {value}"),
+            Instruction::AddI(register, value) => write!(f, "addi {} {value}", register.name()),
+            Instruction::Mul(register, value) => write!(f, "mul {} {value}", register.name()),
+            Instruction::Jmp(offset) => write!(f, "jmp {offset}"),
+            Instruction::Jnz(register, offset) => write!(f, "jnz {} {offset}", register.name()),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Cpu {
+    registers: [i64; 4],
+    pc: usize,
+    cycle: usize,
+    elapsed: usize,
+    program: Vec<Instruction>,
+}
+
+impl Cpu {
+    pub fn new() -> Self {
+        Cpu {
+            registers: [1, 0, 0, 0],
+            pc: 0,
+            cycle: 0,
+            elapsed: 0,
+            program: vec![],
+        }
+    }
+
+    pub fn load(&mut self, program: &[Instruction]) {
+        self.program = program.to_vec();
+        self.registers = [1, 0, 0, 0];
+        self.pc = 0;
+        self.cycle = 0;
+        self.elapsed = 0;
+    }
+
+    pub fn register(&self, register: Register) -> i64 {
+        self.registers[register.index()]
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    pub fn is_between_instructions(&self) -> bool {
+        self.elapsed == 0
+    }
+
+    pub fn current_instruction(&self) -> Option<Instruction> {
+        self.program.get(self.pc).copied()
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.pc >= self.program.len()
+    }
+
+    pub fn tick(&mut self) -> bool {
+        let Some(instruction) = self.current_instruction() else {
+            return false;
+        };
+
+        self.cycle += 1;
+        self.elapsed += 1;
+        if self.elapsed == instruction.cycles() {
+            self.elapsed = 0;
+            self.execute(instruction);
+        }
+
+        true
+    }
+
+    fn execute(&mut self, instruction: Instruction) {
+        let mut offset = 1;
+        match instruction {
+            Instruction::Noop => {}
+            Instruction::AddX(value) => {
+                let x = &mut self.registers[Register::X.index()];
+                *x = x.wrapping_add(value);
+            }
+            Instruction::AddI(register, value) => {
+                let register = &mut self.registers[register.index()];
+                *register = register.wrapping_add(value);
+            }
+            Instruction::Mul(register, value) => {
+                let register = &mut self.registers[register.index()];
+                *register = register.wrapping_mul(value);
+            }
+            Instruction::Jmp(jump) => offset = jump,
+            Instruction::Jnz(register, jump) => {
+                if self.register(register) != 0 {
+                    offset = jump
+                }
+            }
+        }
+
+        self.pc = self
+            .pc
+            .checked_add_signed(offset as isize)
+            .filter(|&pc| pc <= self.program.len())
+            .unwrap_or(self.program.len());
+    }
+
+    pub fn process_instructions(
+        &mut self,
+        instructions: &[Instruction],
+    ) -> Result<Vec<i64>, CycleLimitExceeded> {
+        self.load(instructions);
+        let mut x_values = vec![self.register(Register::X)];
+        loop {
+            x_values.push(self.register(Register::X));
+            if self.is_halted() {
+                return Ok(x_values);
+            }
+            if self.cycle == CYCLE_LIMIT {
+                return Err(CycleLimitExceeded { limit: CYCLE_LIMIT });
+            }
+            self.tick();
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Breakpoint {
+    Cycle(usize),
+    Address(usize),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    Stepped,
+    Breakpoint(Breakpoint),
+    Halted,
+    CycleLimit(usize),
+}
+
+pub struct Debugger {
+    pub cpu: Cpu,
+    breakpoints: Vec<Breakpoint>,
+}
+
+impl Debugger {
+    pub fn new(cpu: Cpu) -> Self {
+        Debugger {
+            cpu,
+            breakpoints: vec![],
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    pub fn remove_breakpoint(&mut self, breakpoint: Breakpoint) {
+        self.breakpoints.retain(|&b| b != breakpoint);
+    }
+
+    fn hit_breakpoint(&self) -> Option<Breakpoint> {
+        self.breakpoints
+            .iter()
+            .copied()
+            .find(|&breakpoint| match breakpoint {
+                Breakpoint::Cycle(cycle) => self.cpu.cycle() + 1 == cycle,
+                Breakpoint::Address(address) => {
+                    self.cpu.is_between_instructions() && self.cpu.pc() == address
+                }
+            })
+    }
+
+    pub fn step_cycle(&mut self) -> StopReason {
+        if self.cpu.tick() {
+            StopReason::Stepped
+        } else {
+            StopReason::Halted
+        }
+    }
+
+    pub fn step_instruction(&mut self) -> StopReason {
+        loop {
+            if !self.cpu.tick() {
+                return StopReason::Halted;
+            }
+            if self.cpu.is_between_instructions() {
+                return StopReason::Stepped;
+            }
+        }
+    }
+
+    pub fn resume(&mut self) -> StopReason {
+        let start = self.cpu.cycle();
+        loop {
+            if self.cpu.cycle() - start == CYCLE_LIMIT {
+                return StopReason::CycleLimit(CYCLE_LIMIT);
+            }
+            if !self.cpu.tick() {
+                return StopReason::Halted;
+            }
+            if let Some(breakpoint) = self.hit_breakpoint() {
+                return StopReason::Breakpoint(breakpoint);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program(source: &str) -> Vec<Instruction> {
+        source.lines().map(|line| line.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn register_arithmetic_wraps() {
+        let mut cpu = Cpu::new();
+        cpu.load(&program(&format!("addi y 1{}", "\nmul y 2".repeat(64))));
+        while cpu.tick() {}
+        assert_eq!(cpu.register(Register::Y), 0);
+    }
+
+    #[test]
+    fn load_resets_the_registers() {
+        let mut cpu = Cpu::new();
+        cpu.load(&program("addx 5\naddi w 3"));
+        while cpu.tick() {}
+        assert_eq!(cpu.register(Register::X), 6);
+
+        cpu.load(&program("noop"));
+        assert_eq!(
+            [Register::X, Register::Y, Register::Z, Register::W].map(|r| cpu.register(r)),
+            [1, 0, 0, 0]
+        );
+        assert_eq!((cpu.pc(), cpu.cycle()), (0, 0));
+    }
+
+    #[test]
+    fn process_instructions_reports_the_cycle_limit() {
+        let mut cpu = Cpu::new();
+        assert_eq!(
+            cpu.process_instructions(&program("noop\naddx 3")),
+            Ok(vec![1, 1, 1, 1, 4])
+        );
+        assert_eq!(
+            cpu.process_instructions(&program("jmp 0")),
+            Err(CycleLimitExceeded { limit: CYCLE_LIMIT })
+        );
+    }
+
+    #[test]
+    fn resume_stops_at_breakpoints_and_the_cycle_limit() {
+        let mut cpu = Cpu::new();
+        cpu.load(&program("noop\njmp 0"));
+        let mut debugger = Debugger::new(cpu);
+        debugger.add_breakpoint(Breakpoint::Cycle(5));
+        assert_eq!(
+            debugger.resume(),
+            StopReason::Breakpoint(Breakpoint::Cycle(5))
+        );
+
+        debugger.remove_breakpoint(Breakpoint::Cycle(5));
+        assert_eq!(debugger.resume(), StopReason::CycleLimit(CYCLE_LIMIT));
+        assert_eq!(debugger.cpu.cycle(), 4 + CYCLE_LIMIT);
+    }
+}
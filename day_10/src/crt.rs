@@ -0,0 +1,172 @@
+use crate::cpu::{Cpu, Register};
+
+const SHADES: [char; 5] = ['.', ':', '+', '*', '#'];
+
+pub struct Crt {
+    width: usize,
+    height: usize,
+    sprite_width: usize,
+    sprites: Vec<Register>,
+    pixels: Vec<u8>,
+    beam: usize,
+}
+
+impl Crt {
+    pub fn new(width: usize, height: usize, sprite_width: usize, sprites: Vec<Register>) -> Self {
+        Crt {
+            width,
+            height,
+            sprite_width,
+            sprites,
+            pixels: vec![0; width * height],
+            beam: 0,
+        }
+    }
+
+    fn max_level(&self) -> u8 {
+        self.sprites.len().max(1) as u8
+    }
+
+    fn is_covered(&self, sprite: i64, column: usize) -> bool {
+        let left = sprite.saturating_sub((self.sprite_width as i64 - 1) / 2);
+        (left..left.saturating_add(self.sprite_width as i64)).contains(&(column as i64))
+    }
+
+    pub fn draw(&mut self, cpu: &Cpu) {
+        if self.pixels.is_empty() {
+            return;
+        }
+
+        let column = self.beam % self.width;
+        self.pixels[self.beam] = self
+            .sprites
+            .iter()
+            .filter(|&&register| self.is_covered(cpu.register(register), column))
+            .count() as u8;
+        self.beam = (self.beam + 1) % self.pixels.len();
+    }
+
+    pub fn run(&mut self, cpu: &mut Cpu) {
+        for _ in 0..self.pixels.len() {
+            if cpu.is_halted() {
+                break;
+            }
+            self.draw(cpu);
+            cpu.tick();
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let max_level = self.max_level() as usize;
+        self.pixels
+            .chunks(self.width)
+            .map(|row| {
+                row.iter()
+                    .map(|&level| SHADES[level as usize * (SHADES.len() - 1) / max_level])
+                    .collect::<String>()
+                    + "\n"
+            })
+            .collect()
+    }
+
+    fn grey_levels(&self) -> impl Iterator<Item = u8> + '_ {
+        let max_level = self.max_level() as usize;
+        self.pixels
+            .iter()
+            .map(move |&level| (level as usize * 255 / max_level) as u8)
+    }
+
+    pub fn to_pgm(&self) -> String {
+        let rows: Vec<String> = self
+            .grey_levels()
+            .collect::<Vec<_>>()
+            .chunks(self.width)
+            .map(|row| {
+                row.iter()
+                    .map(|grey| grey.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect();
+
+        format!(
+            "P2\n{} {}\n255\n{}\n",
+            self.width,
+            self.height,
+            rows.join("\n")
+        )
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        let greys: Vec<u8> = self.grey_levels().collect();
+        let mut scanlines = vec![];
+        for row in greys.chunks(self.width) {
+            scanlines.push(0);
+            scanlines.extend(row);
+        }
+
+        let mut header = vec![];
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        header.extend([8, 0, 0, 0, 0]);
+
+        let mut png = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"IDAT", &zlib_stored(&scanlines));
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (a, b) = bytes.iter().fold((1u32, 0u32), |(a, b), &byte| {
+        let a = (a + byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut output = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = if data.is_empty() {
+        vec![&[]]
+    } else {
+        data.chunks(u16::MAX as usize).collect()
+    };
+
+    for (i, block) in blocks.iter().enumerate() {
+        let is_last = i + 1 == blocks.len();
+        output.push(is_last as u8);
+        output.extend((block.len() as u16).to_le_bytes());
+        output.extend((!(block.len() as u16)).to_le_bytes());
+        output.extend(*block);
+    }
+
+    output.extend(adler32(data).to_be_bytes());
+    output
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
@@ -1,50 +1,187 @@
+use crate::assembler::{assemble, disassemble};
+use crate::cpu::{Breakpoint, Cpu, Debugger, Instruction, Register, StopReason};
+use crate::crt::Crt;
 use std::fs::read_to_string;
+use std::io::{BufRead, Write};
 
-#[derive(Copy, Clone)]
-enum Instruction {
-    Noop,
-    AddX(i32),
-}
+mod assembler;
+mod cpu;
+mod crt;
+mod ocr;
 
-struct Cpu {
-    x: i32,
-}
+fn main() {
+    let input = read_to_string("input").unwrap();
+    let instructions = process_input(&input);
+    let mut cpu = Cpu::new();
 
-impl Cpu {
-    fn process_instructions(&mut self, instructions: &Vec<Instruction>) -> Vec<i32> {
-        let mut x_values = vec![self.x, self.x];
-        for &instruction in instructions {
-            let &next = x_values.last().unwrap();
-            x_values.push(next);
-            if let Instruction::AddX(v) = instruction {
-                let next = x_values.last().unwrap() + v;
-                x_values.push(next);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    match args.as_slice() {
+        [] => {}
+        ["debug"] => {
+            cpu.load(&instructions);
+            debug(Debugger::new(cpu));
+            return;
+        }
+        ["asm", path] => {
+            let source = read_to_string(path).unwrap();
+            match assemble(&source) {
+                Ok(program) => {
+                    for instruction in program {
+                        println!("{instruction}");
+                    }
+                }
+                Err(error) => eprintln!("invalid program: {error}"),
             }
+            return;
+        }
+        ["disasm"] => {
+            let listing = disassemble(&instructions);
+            print!("{listing}");
+            return;
+        }
+        [format @ ("text" | "pgm" | "png"), options @ ..] => {
+            match get_crt(options) {
+                Some(mut crt) => {
+                    cpu.load(&instructions);
+                    crt.run(&mut cpu);
+                    let output = match *format {
+                        "text" => crt.to_text().into_bytes(),
+                        "pgm" => crt.to_pgm().into_bytes(),
+                        _ => crt.to_png(),
+                    };
+                    std::io::stdout().write_all(&output).unwrap();
+                }
+                None => eprintln!(
+                    "usage: day_10 text|pgm|png [width <n>] [height <n>] [sprite <n>] [registers <xyzw>]"
+                ),
+            }
+            return;
+        }
+        _ => {
+            eprintln!("usage: day_10 [debug | asm <file> | disasm | text|pgm|png [options]]");
+            return;
         }
-        x_values
     }
 
-    fn new() -> Self {
-        Cpu { x: 1 }
+    let x_values = match cpu.process_instructions(&instructions) {
+        Ok(x_values) => x_values,
+        Err(error) => {
+            eprintln!("{error}");
+            return;
+        }
+    };
+    let output_1 = get_signal_strengths(&x_values);
+
+    let mut crt = Crt::new(40, 6, 3, vec![Register::X]);
+    cpu.load(&instructions);
+    crt.run(&mut cpu);
+    let output_2 = crt.to_text();
+
+    println!("part 1: {output_1}");
+    match ocr::read(&output_2) {
+        Ok(text) => println!("part 2: {text}"),
+        Err(error) => {
+            println!("part 2: \n{output_2}");
+            eprintln!("{error}");
+        }
     }
 }
 
-fn main() {
-    let input = read_to_string("input").unwrap();
-    let instructions = process_input(&input);
-    let mut cpu = Cpu::new();
+fn get_crt(options: &[&str]) -> Option<Crt> {
+    let (mut width, mut height, mut sprite_width) = (40, 6, 3);
+    let mut sprites = vec![Register::X];
 
-    let x_values = cpu.process_instructions(&instructions);
+    let mut options = options.iter();
+    while let Some(&option) = options.next() {
+        let value = options.next()?;
+        match option {
+            "width" => width = value.parse().ok()?,
+            "height" => height = value.parse().ok()?,
+            "sprite" => sprite_width = value.parse().ok()?,
+            "registers" => {
+                sprites = value
+                    .chars()
+                    .map(|c| c.to_string().parse().ok())
+                    .collect::<Option<_>>()?
+            }
+            _ => return None,
+        }
+    }
 
-    let output_1 = get_signal_strengths(&x_values);
-    let output_2 = render(&get_visible_pixels(&x_values));
+    (width > 0).then(|| Crt::new(width, height, sprite_width, sprites))
+}
 
-    println!("part 1: {output_1}");
-    println!("part 2: \n{output_2}");
+fn print_state(cpu: &Cpu) {
+    let registers: Vec<String> = Register::ALL
+        .iter()
+        .map(|&register| format!("{}={}", register.name(), cpu.register(register)))
+        .collect();
+    let instruction = cpu
+        .current_instruction()
+        .map_or("halted".to_string(), |instruction| instruction.to_string());
+    println!(
+        "cycle {} pc {} ({instruction}) {}",
+        cpu.cycle(),
+        cpu.pc(),
+        registers.join(" ")
+    );
 }
 
-fn get_signal_strengths(x_values: &[i32]) -> i32 {
-    let mut output = 0;
+fn debug(mut debugger: Debugger) {
+    print_state(&debugger.cpu);
+    for line in std::io::stdin().lock().lines() {
+        let line = line.unwrap();
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let breakpoint = |kind: &str, value: &str| match (kind, value.parse()) {
+            ("cycle", Ok(n)) => Some(Breakpoint::Cycle(n)),
+            ("pc", Ok(n)) => Some(Breakpoint::Address(n)),
+            _ => None,
+        };
+
+        let stop_reason = match words.as_slice() {
+            ["break", kind, value] => match breakpoint(kind, value) {
+                Some(breakpoint) => {
+                    debugger.add_breakpoint(breakpoint);
+                    continue;
+                }
+                None => None,
+            },
+            ["delete", kind, value] => match breakpoint(kind, value) {
+                Some(breakpoint) => {
+                    debugger.remove_breakpoint(breakpoint);
+                    continue;
+                }
+                None => None,
+            },
+            ["tick"] => Some(debugger.step_cycle()),
+            ["step"] => Some(debugger.step_instruction()),
+            ["continue"] => Some(debugger.resume()),
+            ["registers"] => Some(StopReason::Stepped),
+            ["quit"] => return,
+            _ => None,
+        };
+
+        match stop_reason {
+            Some(StopReason::Breakpoint(breakpoint)) => println!("hit {breakpoint:?}"),
+            Some(StopReason::Halted) => println!("halted"),
+            Some(StopReason::CycleLimit(limit)) => {
+                println!("still running after {limit} cycles")
+            }
+            Some(StopReason::Stepped) => {}
+            None => {
+                println!(
+                    "commands: break|delete cycle|pc <n>, tick, step, continue, registers, quit"
+                );
+                continue;
+            }
+        }
+        print_state(&debugger.cpu);
+    }
+}
+
+fn get_signal_strengths(x_values: &[i64]) -> i64 {
+    let mut output: i64 = 0;
 
     for (value, cycle) in (20..)
         .step_by(40)
@@ -52,43 +189,11 @@ fn get_signal_strengths(x_values: &[i32]) -> i32 {
         .take_while(|x| x.is_some())
         .flatten()
     {
-        let signal_strength = value * cycle as i32;
-        output += signal_strength
+        let signal_strength = value.wrapping_mul(cycle as i64);
+        output = output.wrapping_add(signal_strength)
     }
     output
 }
 fn process_input(input: &str) -> Vec<Instruction> {
-    input
-        .lines()
-        .map(|line| {
-            if line == "noop" {
-                Instruction::Noop
-            } else {
-                Instruction::AddX(line.strip_prefix("addx ").unwrap().parse().unwrap())
-            }
-        })
-        .collect()
-}
-
-fn get_visible_pixels(x_values: &[i32]) -> Vec<bool> {
-    x_values
-        .iter()
-        .skip(1)
-        .enumerate()
-        .map(|(i, x)| i.rem_euclid(40).abs_diff(*x as usize) <= 1)
-        .collect()
-}
-
-const SCREEN_WIDTH: usize = 40;
-
-fn render(visible_pixels: &[bool]) -> String {
-    visible_pixels
-        .chunks_exact(SCREEN_WIDTH)
-        .map(|line| {
-            line.iter()
-                .map(|&b| if b { '#' } else { '.' })
-                .collect::<String>()
-                + "\n"
-        })
-        .collect()
+    input.lines().map(|line| line.parse().unwrap()).collect()
 }
@@ -0,0 +1,124 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+const GLYPH_WIDTH: usize = 4;
+const GLYPH_HEIGHT: usize = 6;
+const GLYPH_SPACING: usize = 1;
+
+const FONT: [(char, [&str; GLYPH_HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[derive(Debug)]
+pub struct UnrecognisedGlyph {
+    pub index: usize,
+    pub column: usize,
+    pub row: usize,
+    pub pattern: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct OcrError {
+    pub partial: String,
+    pub glyphs: Vec<UnrecognisedGlyph>,
+}
+
+impl Display for OcrError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "could not read {:?}:", self.partial)?;
+        for glyph in &self.glyphs {
+            write!(
+                f,
+                "\nglyph {} at row {}, column {}:\n{}",
+                glyph.index,
+                glyph.row,
+                glyph.column,
+                glyph.pattern.join("\n")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for OcrError {}
+
+fn get_glyph(lines: &[Vec<char>], column: usize) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| {
+            (column..column + GLYPH_WIDTH + GLYPH_SPACING)
+                .map(|x| if line.get(x) == Some(&'#') { '#' } else { '.' })
+                .collect()
+        })
+        .collect()
+}
+
+fn recognise_glyph(pattern: &[String]) -> Option<char> {
+    if pattern.iter().all(|line| !line.contains('#')) {
+        return Some(' ');
+    }
+
+    FONT.iter()
+        .find(|(_, glyph)| {
+            glyph
+                .iter()
+                .zip(pattern)
+                .all(|(a, b)| b.starts_with(a) && b[a.len()..].chars().all(|c| c == '.'))
+        })
+        .map(|&(c, _)| c)
+}
+
+pub fn read(screen: &str) -> Result<String, OcrError> {
+    let lines: Vec<Vec<char>> = screen.lines().map(|line| line.chars().collect()).collect();
+    let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+
+    let mut text_lines = vec![];
+    let mut glyphs = vec![];
+    for (row, band) in lines.chunks(GLYPH_HEIGHT).enumerate() {
+        let mut text = String::new();
+        for column in (0..width).step_by(GLYPH_WIDTH + GLYPH_SPACING) {
+            let pattern = get_glyph(band, column);
+            match recognise_glyph(&pattern).filter(|_| band.len() == GLYPH_HEIGHT) {
+                Some(c) => text.push(c),
+                None => {
+                    text.push('?');
+                    glyphs.push(UnrecognisedGlyph {
+                        index: column / (GLYPH_WIDTH + GLYPH_SPACING),
+                        column,
+                        row: row * GLYPH_HEIGHT,
+                        pattern,
+                    });
+                }
+            }
+        }
+        text_lines.push(text.trim_end().to_string());
+    }
+
+    let text = text_lines.join("\n");
+    if glyphs.is_empty() {
+        Ok(text)
+    } else {
+        Err(OcrError {
+            partial: text,
+            glyphs,
+        })
+    }
+}
@@ -0,0 +1,226 @@
+use crate::worry::{Worry, WorryError};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl Operator {
+    fn symbol(self) -> char {
+        match self {
+            Operator::Add => '+',
+            Operator::Sub => '-',
+            Operator::Mul => '*',
+            Operator::Div => '/',
+            Operator::Rem => '%',
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expression {
+    Variable,
+    Constant(u64),
+    Binary(Box<Expression>, Operator, Box<Expression>),
+}
+
+impl Expression {
+    pub fn evaluate(&self, variable: &Worry) -> Result<Worry, WorryError> {
+        let (lhs, operator, rhs) = match self {
+            Expression::Variable => return Ok(variable.clone()),
+            Expression::Constant(value) => return Ok(variable.constant(*value)),
+            Expression::Binary(lhs, operator, rhs) => (lhs.evaluate(variable)?, *operator, rhs),
+        };
+
+        let divisor = |rhs: &Expression| match rhs {
+            Expression::Constant(value) => Ok(*value),
+            rhs => rhs.evaluate(variable)?.value(),
+        };
+
+        match operator {
+            Operator::Add => lhs.add(&rhs.evaluate(variable)?),
+            Operator::Sub => lhs.sub(&rhs.evaluate(variable)?),
+            Operator::Mul => lhs.mul(&rhs.evaluate(variable)?),
+            Operator::Div => lhs.div(divisor(rhs)?),
+            Operator::Rem => lhs.rem(divisor(rhs)?),
+        }
+    }
+
+    pub fn moduli(&self) -> Vec<u64> {
+        match self {
+            Expression::Binary(lhs, operator, rhs) => {
+                let mut moduli = lhs.moduli();
+                moduli.extend(rhs.moduli());
+                if let (Operator::Rem, Expression::Constant(modulus)) = (operator, rhs.as_ref()) {
+                    moduli.push(*modulus);
+                }
+                moduli
+            }
+            _ => vec![],
+        }
+    }
+
+    pub fn is_exact_modulo(&self, modulus: u64) -> bool {
+        match self {
+            Expression::Constant(value) => *value < modulus,
+            Expression::Binary(_, Operator::Rem, rhs) => {
+                matches!(rhs.as_ref(), Expression::Constant(divisor) if modulus.is_multiple_of(*divisor))
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ExpressionErrorKind {
+    Expected {
+        expected: &'static str,
+        found: Option<char>,
+    },
+    Overflow,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExpressionError {
+    pub column: usize,
+    pub kind: ExpressionErrorKind,
+}
+
+impl Display for ExpressionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "column {}: ", self.column)?;
+        match self.kind {
+            ExpressionErrorKind::Expected {
+                expected,
+                found: Some(c),
+            } => write!(f, "expected {expected}, found {c:?}"),
+            ExpressionErrorKind::Expected {
+                expected,
+                found: None,
+            } => write!(f, "expected {expected}, found end of expression"),
+            ExpressionErrorKind::Overflow => write!(f, "constant too large"),
+        }
+    }
+}
+
+impl Error for ExpressionError {}
+
+struct Cursor<'a> {
+    text: &'a str,
+    variable: &'a str,
+    position: usize,
+}
+
+impl Cursor<'_> {
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.text[self.position..]
+            .chars()
+            .next()
+            .filter(|c| c.is_whitespace())
+        {
+            self.position += c.len_utf8();
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.text[self.position..].chars().next()
+    }
+
+    fn error(&self, position: usize, kind: ExpressionErrorKind) -> ExpressionError {
+        ExpressionError {
+            column: position + 1,
+            kind,
+        }
+    }
+
+    fn expected(&mut self, expected: &'static str) -> ExpressionError {
+        let found = self.peek();
+        self.error(
+            self.position,
+            ExpressionErrorKind::Expected { expected, found },
+        )
+    }
+
+    fn operator(&mut self, operators: &[Operator]) -> Option<Operator> {
+        let c = self.peek()?;
+        let operator = operators
+            .iter()
+            .copied()
+            .find(|operator| operator.symbol() == c)?;
+        self.position += 1;
+        Some(operator)
+    }
+
+    fn sum(&mut self) -> Result<Expression, ExpressionError> {
+        let mut expression = self.product()?;
+        while let Some(operator) = self.operator(&[Operator::Add, Operator::Sub]) {
+            expression =
+                Expression::Binary(Box::new(expression), operator, Box::new(self.product()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn product(&mut self) -> Result<Expression, ExpressionError> {
+        let mut expression = self.atom()?;
+        while let Some(operator) = self.operator(&[Operator::Mul, Operator::Div, Operator::Rem]) {
+            expression = Expression::Binary(Box::new(expression), operator, Box::new(self.atom()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn atom(&mut self) -> Result<Expression, ExpressionError> {
+        match self.peek() {
+            Some('(') => {
+                self.position += 1;
+                let expression = self.sum()?;
+                if self.peek() != Some(')') {
+                    return Err(self.expected("')'"));
+                }
+                self.position += 1;
+                Ok(expression)
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let start = self.position;
+                let length = self.text[start..]
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(self.text.len() - start);
+                self.position += length;
+                self.text[start..self.position]
+                    .parse()
+                    .map(Expression::Constant)
+                    .map_err(|_| self.error(start, ExpressionErrorKind::Overflow))
+            }
+            _ if self.text[self.position..].starts_with(self.variable)
+                && !self.text[self.position + self.variable.len()..]
+                    .starts_with(|c: char| c.is_ascii_alphanumeric()) =>
+            {
+                self.position += self.variable.len();
+                Ok(Expression::Variable)
+            }
+            _ => Err(self.expected("a number, '(' or a variable")),
+        }
+    }
+}
+
+pub fn parse(text: &str, variable: &str) -> Result<Expression, ExpressionError> {
+    let mut cursor = Cursor {
+        text,
+        variable,
+        position: 0,
+    };
+    let expression = cursor.sum()?;
+    if cursor.peek().is_some() {
+        return Err(cursor.expected("an operator"));
+    }
+
+    Ok(expression)
+}
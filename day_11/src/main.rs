@@ -1,176 +1,131 @@
+use crate::monkey::{process_input, Monkey};
+use crate::route::{fast_forward, item_histories, trace};
+use crate::worry::{Worry, WorryError, WorryMode};
 use itertools::Itertools;
-use num::integer::lcm;
-use std::collections::{HashMap, VecDeque};
+use std::error::Error;
 use std::fs::read_to_string;
 
-#[derive(Clone, Debug)]
-enum ItemValue {
-    Normal(u32),
-    Big(HashMap<u32, u32>),
-}
+mod expression;
+mod monkey;
+mod route;
+mod worry;
 
-impl ItemValue {
-    fn new(n: u32, divisors: Vec<u32>) -> Self {
-        ItemValue::Big(divisors.into_iter().map(|d| (d, n.rem_euclid(d))).collect())
-    }
+enum Command {
+    Run,
+    Rounds(u64),
+    Trace(u64),
+    Cycles,
 }
 
-impl<T: Into<u32>> std::ops::Add<T> for ItemValue {
-    type Output = Self;
-
-    fn add(self, rhs: T) -> Self::Output {
-        let rhs = rhs.into();
-        match self {
-            ItemValue::Normal(x) => ItemValue::Normal(x + rhs),
+fn main() {
+    let input = read_to_string("input").unwrap();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let (exact_mode, args) = match args.as_slice() {
+        ["big", args @ ..] => (WorryMode::Unbounded, args),
+        args => (WorryMode::Checked, args),
+    };
+    let command = match args {
+        [] => Some(Command::Run),
+        ["rounds", n] => n.parse().ok().map(Command::Rounds),
+        ["trace", n] => n.parse().ok().map(Command::Trace),
+        ["cycles"] => Some(Command::Cycles),
+        _ => None,
+    };
+    let Some(command) = command else {
+        eprintln!("usage: day_11 [big] [rounds <n> | trace <n> | cycles]");
+        return;
+    };
 
-            ItemValue::Big(remainders) => ItemValue::Big(
-                remainders
-                    .iter()
-                    .map(|(&divisor, &remainder)| (divisor, (remainder + rhs).rem_euclid(divisor)))
-                    .collect(),
-            ),
-        }
+    if let Err(error) = run(&input, exact_mode, command) {
+        eprintln!("{error}");
     }
 }
 
-impl<T: Into<u32>> std::ops::Mul<T> for ItemValue {
-    type Output = Self;
-
-    fn mul(self, rhs: T) -> Self::Output {
-        let rhs = rhs.into();
-        match self {
-            ItemValue::Normal(x) => ItemValue::Normal(x * rhs),
-
-            ItemValue::Big(remainders) => ItemValue::Big(
-                remainders
-                    .iter()
-                    .map(|(&divisor, &remainder)| (divisor, (remainder * rhs).rem_euclid(divisor)))
-                    .collect(),
-            ),
+fn run(input: &str, exact_mode: WorryMode, command: Command) -> Result<(), Box<dyn Error>> {
+    let monkeys = process_input(input)?;
+    let moduli: Vec<u64> = monkeys.iter().flat_map(|monkey| monkey.moduli()).collect();
+
+    let monkeys_1 = with_mode(&monkeys, exact_mode)?;
+    let monkeys_2 = with_mode(&monkeys, WorryMode::residue(&moduli)?)?;
+
+    match command {
+        Command::Run => {}
+        Command::Rounds(round_count) => {
+            let activity = fast_forward(&monkeys_2, round_count, false)?;
+            let monkey_business: u128 = activity
+                .iter()
+                .map(|&count| count as u128)
+                .k_largest(2)
+                .product();
+            println!("activity after {round_count} rounds: {activity:?}");
+            println!("monkey business: {monkey_business}");
+            return Ok(());
         }
-    }
-}
-
-impl From<ItemValue> for u32 {
-    fn from(item_value: ItemValue) -> Self {
-        match item_value {
-            ItemValue::Normal(x) => x,
-            ItemValue::Big(remainders) => {
-                remainders
-                    .into_iter()
-                    .reduce(|(divisor_1, remainder_1), (divisor_2, remainder_2)| {
-                        (
-                            lcm(divisor_1, divisor_2),
-                            (0..divisor_2)
-                                .map(|n| divisor_1 * n + remainder_1)
-                                .find(|&n| n.rem_euclid(divisor_2) == remainder_2)
-                                .unwrap(),
-                        )
-                    })
-                    .unwrap()
-                    .1
+        Command::Trace(round_count) => {
+            println!("item,round,monkey,worry,new_worry,target");
+            for throw in trace(&monkeys_2, round_count, false)? {
+                println!(
+                    "{},{},{},{},{},{}",
+                    throw.item,
+                    throw.round,
+                    throw.monkey,
+                    throw.worry,
+                    throw.new_worry,
+                    throw.target
+                );
             }
+            return Ok(());
         }
-    }
-}
-
-#[derive(Clone)]
-enum Operation {
-    Add(u32),
-    Mul(u32),
-    Square,
-}
-
-impl Operation {
-    fn apply(&self, input: ItemValue) -> ItemValue {
-        match self {
-            Operation::Add(other) => input + *other,
-            Operation::Mul(other) => input * *other,
-            Operation::Square => input.clone() * input,
+        Command::Cycles => {
+            for history in item_histories(&monkeys_2, u64::MAX, false)? {
+                match history.cycle {
+                    Some(cycle) => println!(
+                        "item {}: cycle of {} rounds from round {}",
+                        history.item, cycle.length, cycle.start
+                    ),
+                    None => println!("item {}: no cycle", history.item),
+                }
+            }
+            return Ok(());
         }
     }
-}
-
-#[derive(Clone)]
-struct DivisibilityTest {
-    divisor: u32,
-    if_true: usize,
-    if_false: usize,
-}
-
-impl DivisibilityTest {
-    fn apply(&self, input: &ItemValue) -> usize {
-        let result = match input {
-            ItemValue::Big(remainders) => remainders
-                .get(&self.divisor)
-                .map(|&n| n == 0)
-                .unwrap_or(false),
-
-            ItemValue::Normal(n) => n.rem_euclid(self.divisor) == 0,
-        };
 
-        if result {
-            self.if_true
-        } else {
-            self.if_false
+    let output_1 = simulate_simians(monkeys_1, 20, true)?;
+    match simulate_simians(monkeys_2, 10000, false) {
+        Ok(output_2) => println!("part 1: {output_1} part 2: {output_2}"),
+        Err(error) => {
+            println!("part 1: {output_1}");
+            return Err(error.into());
         }
     }
+    Ok(())
 }
 
-#[derive(Clone)]
-struct Monkey {
-    activity: u64,
-    items: VecDeque<ItemValue>,
-    operation: Operation,
-    divisibility_test: DivisibilityTest,
-}
-
-fn main() {
-    let input = read_to_string("input").unwrap();
-
-    let monkeys_1 = process_input(&input, true);
-    let monkeys_2 = process_input(&input, false);
-
-    let output_1 = simulate_simians(monkeys_1, 20, true);
-    let output_2 = simulate_simians(monkeys_2, 10000, false);
-
-    println!("part 1: {output_1} part 2: {output_2}");
-}
-
-fn process_input(input: &str, divide_worry_levels: bool) -> Vec<Monkey> {
-    let divisors = if divide_worry_levels {
-        None
-    } else {
-        Some(
-            input
-                .lines()
-                .map(|s| s.trim())
-                .filter_map(|s| s.strip_prefix("Test: divisible by "))
-                .map(|s| s.parse().unwrap())
-                .collect(),
-        )
-    };
-    input
-        .split("\n\n")
-        .map(|s| generate_monkey(s, divisors.clone()))
+fn with_mode(monkeys: &[Monkey], mode: WorryMode) -> Result<Vec<Monkey>, WorryError> {
+    monkeys
+        .iter()
+        .map(|monkey| monkey.with_mode(mode))
         .collect()
 }
 
-fn round(monkeys: &mut [Monkey], divide_worry_level: bool) {
-    (0..monkeys.len()).for_each(|i| turn(monkeys, i, divide_worry_level))
+fn round(monkeys: &mut [Monkey], divide_worry_level: bool) -> Result<(), WorryError> {
+    (0..monkeys.len()).try_for_each(|i| turn(monkeys, i, divide_worry_level))
 }
 
-fn turn(monkeys: &mut [Monkey], monkey_index: usize, divide_worry_level: bool) {
+fn turn(
+    monkeys: &mut [Monkey],
+    monkey_index: usize,
+    divide_worry_level: bool,
+) -> Result<(), WorryError> {
     let monkey = monkeys.get_mut(monkey_index).unwrap();
-    let mut items_to_throw: Vec<(ItemValue, usize)> = vec![];
+    let mut items_to_throw: Vec<(Worry, usize)> = vec![];
     while let Some(mut item_value) = monkey.items.pop_front() {
-        item_value = monkey.operation.apply(item_value);
+        item_value = monkey.operation.evaluate(&item_value)?;
         if divide_worry_level {
-            if let ItemValue::Normal(x) = item_value {
-                item_value = ItemValue::Normal(x / 3)
-            }
+            item_value = item_value.div(3)?;
         }
-        let next_monkey_index = monkey.divisibility_test.apply(&item_value);
+        let next_monkey_index = monkey.test.apply(&item_value)?;
         items_to_throw.push((item_value, next_monkey_index));
         monkey.activity += 1;
     }
@@ -178,96 +133,130 @@ fn turn(monkeys: &mut [Monkey], monkey_index: usize, divide_worry_level: bool) {
     for (item_value, next_monkey_index) in items_to_throw {
         monkeys[next_monkey_index].items.push_back(item_value)
     }
-}
 
-fn generate_monkey(input: &str, divisors: Option<Vec<u32>>) -> Monkey {
-    let lines = input.lines().collect_vec();
-    let items: VecDeque<ItemValue> = get_starting_items(lines[1], &divisors);
-    let operation = get_operation(lines[2]);
-    let divisibility_test = get_divisibility_test(&lines[3..]);
+    Ok(())
+}
 
-    Monkey {
-        activity: 0,
-        items,
-        operation,
-        divisibility_test,
+fn simulate_simians(
+    mut monkeys: Vec<Monkey>,
+    round_count: u32,
+    divide_worry_level: bool,
+) -> Result<u64, WorryError> {
+    for _ in 0..round_count {
+        round(&mut monkeys, divide_worry_level)?;
     }
-}
+    let monkey_business: u64 = monkeys.iter().map(|m| m.activity).k_largest(2).product();
 
-fn get_starting_items(input: &str, divisors: &Option<Vec<u32>>) -> VecDeque<ItemValue> {
-    input
-        .trim()
-        .strip_prefix("Starting items: ")
-        .unwrap()
-        .split(", ")
-        .map(|s| s.parse().unwrap())
-        .map(|n| match divisors {
-            None => ItemValue::Normal(n),
-            Some(divisors) => ItemValue::new(n, divisors.clone()),
-        })
-        .collect()
+    Ok(monkey_business)
 }
 
-fn get_operation(input: &str) -> Operation {
-    let text_to_process = input.trim().strip_prefix("Operation: new = ").unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if text_to_process == "old * old" {
-        return Operation::Square;
-    };
+    const SAMPLE: &str = include_str!("../practice");
 
-    if text_to_process.starts_with("old *") {
-        return Operation::Mul(
-            text_to_process
-                .strip_prefix("old * ")
-                .unwrap()
-                .parse()
-                .unwrap(),
-        );
-    };
+    fn with_rules(rules: &str) -> String {
+        SAMPLE.replace(
+            "  Test: divisible by 17\n    If true: throw to monkey 0\n    If false: throw to monkey 1",
+            &format!("  Test:\n{rules}    Otherwise: throw to monkey 1"),
+        )
+    }
 
-    Operation::Add(
-        text_to_process
-            .strip_prefix("old + ")
-            .unwrap()
-            .parse()
-            .unwrap(),
-    )
-}
+    fn simulate_in(input: &str, mode: WorryMode, round_count: u32) -> Result<u64, WorryError> {
+        let monkeys = process_input(input).unwrap();
+        simulate_simians(with_mode(&monkeys, mode)?, round_count, false)
+    }
 
-fn get_divisibility_test(input: &[&str]) -> DivisibilityTest {
-    let divisor: u32 = input[0]
-        .trim()
-        .strip_prefix("Test: divisible by ")
-        .unwrap()
-        .parse()
-        .unwrap();
+    fn residue_mode(input: &str) -> WorryMode {
+        let monkeys = process_input(input).unwrap();
+        let moduli: Vec<u64> = monkeys.iter().flat_map(|monkey| monkey.moduli()).collect();
+        WorryMode::residue(&moduli).unwrap()
+    }
 
-    let if_true = input[1]
-        .trim()
-        .strip_prefix("If true: throw to monkey ")
-        .unwrap()
-        .parse()
-        .unwrap();
+    fn simulated_activity(
+        monkeys: &[Monkey],
+        round_counts: &[u64],
+        divide_worry_level: bool,
+    ) -> Vec<Vec<u64>> {
+        let mut monkeys = monkeys.to_vec();
+        let mut activities = vec![];
+        for round_count in 0..=round_counts.iter().copied().max().unwrap_or(0) {
+            if round_counts.contains(&round_count) {
+                activities.push(monkeys.iter().map(|monkey| monkey.activity).collect());
+            }
+            round(&mut monkeys, divide_worry_level).unwrap();
+        }
+        activities
+    }
 
-    let if_false = input[2]
-        .trim()
-        .strip_prefix("If false: throw to monkey ")
-        .unwrap()
-        .parse()
-        .unwrap();
+    #[test]
+    fn fast_forward_matches_simulation_around_cycles() {
+        let monkeys = process_input(SAMPLE).unwrap();
+        let monkeys = with_mode(&monkeys, residue_mode(SAMPLE)).unwrap();
+        let mut round_counts = vec![0, 1, 10000];
+        for history in item_histories(&monkeys, u64::MAX, false).unwrap() {
+            let cycle = history.cycle.unwrap();
+            round_counts.extend([
+                cycle.start.saturating_sub(1),
+                cycle.start,
+                cycle.start + 1,
+                cycle.start + cycle.length,
+                cycle.start + 3 * cycle.length - 1,
+                cycle.start + 3 * cycle.length + 7,
+            ]);
+        }
+        round_counts.sort();
+        round_counts.dedup();
+
+        let expected = simulated_activity(&monkeys, &round_counts, false);
+        for (&round_count, expected) in round_counts.iter().zip(expected) {
+            assert_eq!(
+                fast_forward(&monkeys, round_count, false),
+                Ok(expected),
+                "{round_count} rounds"
+            );
+        }
+    }
 
-    DivisibilityTest {
-        divisor,
-        if_true,
-        if_false,
+    #[test]
+    fn fast_forward_matches_simulation_with_division() {
+        let monkeys = process_input(SAMPLE).unwrap();
+        let round_counts: Vec<u64> = (0..=20).collect();
+        let expected = simulated_activity(&monkeys, &round_counts, true);
+        for (&round_count, expected) in round_counts.iter().zip(expected) {
+            assert_eq!(fast_forward(&monkeys, round_count, true), Ok(expected));
+        }
+        assert_eq!(simulate_simians(monkeys, 20, true), Ok(10605));
     }
-}
 
-fn simulate_simians(mut monkeys: Vec<Monkey>, round_count: u32, divide_worry_level: bool) -> u64 {
-    for _ in 0..round_count {
-        round(&mut monkeys, divide_worry_level);
+    #[test]
+    fn residue_conditions_match_exact_worry_levels() {
+        let input = with_rules(
+            "    If new % 5 == 3: throw to monkey 0\n    If 4 != new % 17: throw to monkey 2\n",
+        );
+        assert_eq!(
+            simulate_in(&input, residue_mode(&input), 20),
+            simulate_in(&input, WorryMode::Unbounded, 20)
+        );
+        assert_eq!(
+            simulate_in(SAMPLE, residue_mode(SAMPLE), 10000),
+            Ok(2713310158)
+        );
     }
-    let monkey_business: u64 = monkeys.iter().map(|m| m.activity).k_largest(2).product();
 
-    monkey_business
+    #[test]
+    fn residue_conditions_refuse_inexact_comparisons() {
+        // 482885 is the modulus itself, so it reduces to 0
+        for condition in ["new == 3", "new % 5 == 482885", "new % 5 == new / 2"] {
+            let input = with_rules(&format!("    If {condition}: throw to monkey 2\n"));
+            assert_eq!(
+                simulate_in(&input, residue_mode(&input), 1),
+                Err(WorryError::UnsupportedInResidueSpace(
+                    "comparing worry levels"
+                )),
+                "{condition}"
+            );
+        }
+    }
 }
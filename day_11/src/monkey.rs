@@ -0,0 +1,277 @@
+use crate::expression::{parse, Expression, ExpressionError};
+use crate::worry::{Worry, WorryError, WorryMode};
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub enum InputErrorKind {
+    Malformed(String),
+    Expression(ExpressionError),
+    MissingOtherwise,
+    UnknownMonkey(usize),
+}
+
+#[derive(Debug)]
+pub struct InputError {
+    pub line: usize,
+    pub kind: InputErrorKind,
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            InputErrorKind::Malformed(text) => write!(f, "malformed line {text:?}"),
+            InputErrorKind::Expression(error) => write!(f, "{error}"),
+            InputErrorKind::MissingOtherwise => {
+                write!(f, "test has no \"Otherwise\" or \"If false\" target")
+            }
+            InputErrorKind::UnknownMonkey(monkey) => write!(f, "there is no monkey {monkey}"),
+        }
+    }
+}
+
+impl Error for InputError {}
+
+#[derive(Clone)]
+pub enum Condition {
+    DivisibleBy(u64),
+    Equal(Expression, Expression),
+    NotEqual(Expression, Expression),
+    Always,
+}
+
+impl Condition {
+    fn holds(&self, worry: &Worry) -> Result<bool, WorryError> {
+        match self {
+            Condition::Equal(lhs, rhs) | Condition::NotEqual(lhs, rhs)
+                if matches!(worry.mode(), WorryMode::Residue(modulus)
+                    if !lhs.is_exact_modulo(modulus) || !rhs.is_exact_modulo(modulus)) =>
+            {
+                Err(WorryError::UnsupportedInResidueSpace(
+                    "comparing worry levels",
+                ))
+            }
+            Condition::DivisibleBy(divisor) => worry.is_divisible_by(*divisor),
+            Condition::Equal(lhs, rhs) => Ok(lhs.evaluate(worry)? == rhs.evaluate(worry)?),
+            Condition::NotEqual(lhs, rhs) => Ok(lhs.evaluate(worry)? != rhs.evaluate(worry)?),
+            Condition::Always => Ok(true),
+        }
+    }
+
+    fn moduli(&self) -> Vec<u64> {
+        match self {
+            Condition::DivisibleBy(divisor) => vec![*divisor],
+            Condition::Equal(lhs, rhs) | Condition::NotEqual(lhs, rhs) => {
+                let mut moduli = lhs.moduli();
+                moduli.extend(rhs.moduli());
+                moduli
+            }
+            Condition::Always => vec![],
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Test {
+    rules: Vec<(Condition, usize)>,
+}
+
+impl Test {
+    pub fn apply(&self, worry: &Worry) -> Result<usize, WorryError> {
+        for (condition, target) in &self.rules {
+            if condition.holds(worry)? {
+                return Ok(*target);
+            }
+        }
+
+        unreachable!("tests always end with an unconditional rule")
+    }
+}
+
+#[derive(Clone)]
+pub struct Monkey {
+    pub activity: u64,
+    pub items: VecDeque<Worry>,
+    pub operation: Expression,
+    pub test: Test,
+}
+
+impl Monkey {
+    pub fn moduli(&self) -> Vec<u64> {
+        let mut moduli = self.operation.moduli();
+        for (condition, _) in &self.test.rules {
+            moduli.extend(condition.moduli());
+        }
+        moduli
+    }
+
+    pub fn with_mode(&self, mode: WorryMode) -> Result<Monkey, WorryError> {
+        Ok(Monkey {
+            items: self
+                .items
+                .iter()
+                .map(|item| Ok(mode.worry(item.value()?)))
+                .collect::<Result<_, WorryError>>()?,
+            ..self.clone()
+        })
+    }
+}
+
+struct Line<'a> {
+    number: usize,
+    text: &'a str,
+}
+
+impl Line<'_> {
+    fn error(&self, kind: InputErrorKind) -> InputError {
+        InputError {
+            line: self.number,
+            kind,
+        }
+    }
+
+    fn malformed(&self) -> InputError {
+        self.error(InputErrorKind::Malformed(self.text.to_string()))
+    }
+
+    fn field(&self, prefix: &str) -> Result<&str, InputError> {
+        self.text
+            .trim()
+            .strip_prefix(prefix)
+            .ok_or_else(|| self.malformed())
+    }
+
+    fn number<T: std::str::FromStr>(&self, text: &str) -> Result<T, InputError> {
+        text.trim().parse().map_err(|_| self.malformed())
+    }
+
+    fn target(&self, text: &str, monkey_count: usize) -> Result<usize, InputError> {
+        let target = self.number(text)?;
+        if target >= monkey_count {
+            return Err(self.error(InputErrorKind::UnknownMonkey(target)));
+        }
+
+        Ok(target)
+    }
+
+    fn expression(&self, text: &str, variable: &str) -> Result<Expression, InputError> {
+        parse(text, variable).map_err(|error| self.error(InputErrorKind::Expression(error)))
+    }
+}
+
+fn get_condition(line: &Line, text: &str) -> Result<Condition, InputError> {
+    if let Some(divisor) = text.strip_prefix("divisible by ") {
+        return Ok(Condition::DivisibleBy(line.number(divisor)?));
+    }
+
+    if let Some((lhs, rhs)) = text.split_once("!=") {
+        return Ok(Condition::NotEqual(
+            line.expression(lhs, "new")?,
+            line.expression(rhs, "new")?,
+        ));
+    }
+
+    match text.split_once("==") {
+        Some((lhs, rhs)) => Ok(Condition::Equal(
+            line.expression(lhs, "new")?,
+            line.expression(rhs, "new")?,
+        )),
+        None => Err(line.malformed()),
+    }
+}
+
+fn get_rule(line: &Line, monkey_count: usize) -> Result<(Condition, usize), InputError> {
+    let text = line.text.trim();
+    let (condition, target) = text.split_once(':').ok_or_else(|| line.malformed())?;
+    let target = target
+        .trim()
+        .strip_prefix("throw to monkey ")
+        .ok_or_else(|| line.malformed())?;
+    let target = line.target(target, monkey_count)?;
+
+    let condition = match condition {
+        "Otherwise" => Condition::Always,
+        condition => get_condition(
+            line,
+            condition
+                .strip_prefix("If ")
+                .ok_or_else(|| line.malformed())?,
+        )?,
+    };
+
+    Ok((condition, target))
+}
+
+fn get_test(lines: &[Line], monkey_count: usize) -> Result<Test, InputError> {
+    let header = lines[0].field("Test:")?.trim();
+    let rules = if header.is_empty() {
+        lines[1..]
+            .iter()
+            .map(|line| get_rule(line, monkey_count))
+            .collect::<Result<_, _>>()?
+    } else {
+        let [_, if_true, if_false] = lines else {
+            return Err(lines[0].error(InputErrorKind::MissingOtherwise));
+        };
+        vec![
+            (
+                get_condition(&lines[0], header)?,
+                if_true.target(if_true.field("If true: throw to monkey ")?, monkey_count)?,
+            ),
+            (
+                Condition::Always,
+                if_false.target(if_false.field("If false: throw to monkey ")?, monkey_count)?,
+            ),
+        ]
+    };
+
+    match rules.last() {
+        Some((Condition::Always, _)) => Ok(Test { rules }),
+        _ => Err(lines[lines.len() - 1].error(InputErrorKind::MissingOtherwise)),
+    }
+}
+
+fn get_monkey(lines: &[Line], monkey_count: usize) -> Result<Monkey, InputError> {
+    if lines.len() < 4 {
+        return Err(lines[lines.len() - 1].malformed());
+    }
+
+    let items = lines[1].field("Starting items:")?;
+    let items = items
+        .split(',')
+        .filter(|item| !item.trim().is_empty())
+        .map(|item| Ok(WorryMode::Checked.worry(lines[1].number(item)?)))
+        .collect::<Result<_, InputError>>()?;
+    let operation = lines[2].expression(lines[2].field("Operation: new =")?, "old")?;
+    let test = get_test(&lines[3..], monkey_count)?;
+
+    Ok(Monkey {
+        activity: 0,
+        items,
+        operation,
+        test,
+    })
+}
+
+pub fn process_input(input: &str) -> Result<Vec<Monkey>, InputError> {
+    let lines: Vec<Line> = input
+        .lines()
+        .enumerate()
+        .map(|(index, text)| Line {
+            number: index + 1,
+            text,
+        })
+        .collect();
+
+    let blocks: Vec<&[Line]> = lines
+        .split(|line| line.text.trim().is_empty())
+        .filter(|block| !block.is_empty())
+        .collect();
+
+    blocks
+        .iter()
+        .map(|block| get_monkey(block, blocks.len()))
+        .collect()
+}
@@ -0,0 +1,177 @@
+use crate::monkey::Monkey;
+use crate::worry::{Worry, WorryError};
+use std::collections::HashMap;
+
+pub struct Throw {
+    pub item: usize,
+    pub round: u64,
+    pub monkey: usize,
+    pub worry: Worry,
+    pub new_worry: Worry,
+    pub target: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: u64,
+    pub length: u64,
+}
+
+pub struct ItemHistory {
+    pub item: usize,
+    inspections: Vec<Vec<usize>>,
+    pub cycle: Option<Cycle>,
+}
+
+type State = (usize, Worry);
+
+fn starting_items(monkeys: &[Monkey]) -> Vec<State> {
+    monkeys
+        .iter()
+        .enumerate()
+        .flat_map(|(index, monkey)| monkey.items.iter().map(move |worry| (index, worry.clone())))
+        .collect()
+}
+
+fn item_round(
+    monkeys: &[Monkey],
+    item: usize,
+    round: u64,
+    (mut monkey, mut worry): State,
+    divide_worry_level: bool,
+    throws: &mut Vec<Throw>,
+) -> Result<State, WorryError> {
+    loop {
+        let mut new_worry = monkeys[monkey].operation.evaluate(&worry)?;
+        if divide_worry_level {
+            new_worry = new_worry.div(3)?;
+        }
+        let target = monkeys[monkey].test.apply(&new_worry)?;
+        throws.push(Throw {
+            item,
+            round,
+            monkey,
+            worry,
+            new_worry: new_worry.clone(),
+            target,
+        });
+
+        worry = new_worry;
+        if target <= monkey {
+            return Ok((target, worry));
+        }
+        monkey = target;
+    }
+}
+
+impl ItemHistory {
+    fn new(
+        monkeys: &[Monkey],
+        item: usize,
+        mut state: State,
+        round_count: u64,
+        divide_worry_level: bool,
+    ) -> Result<Self, WorryError> {
+        let mut seen: HashMap<State, u64> = HashMap::new();
+        let mut inspections = vec![];
+        let mut throws = vec![];
+        for round in 0..round_count {
+            if let Some(&start) = seen.get(&state) {
+                return Ok(ItemHistory {
+                    item,
+                    inspections,
+                    cycle: Some(Cycle {
+                        start,
+                        length: round - start,
+                    }),
+                });
+            }
+
+            seen.insert(state.clone(), round);
+            throws.clear();
+            state = item_round(monkeys, item, round, state, divide_worry_level, &mut throws)?;
+            inspections.push(throws.iter().map(|throw| throw.monkey).collect());
+        }
+
+        Ok(ItemHistory {
+            item,
+            inspections,
+            cycle: None,
+        })
+    }
+
+    fn count(&self, rounds: std::ops::Range<usize>, activity: &mut [u64], repeats: u64) {
+        for monkeys in &self.inspections[rounds] {
+            for &monkey in monkeys {
+                activity[monkey] += repeats;
+            }
+        }
+    }
+
+    pub fn activity(&self, round_count: u64, monkey_count: usize) -> Vec<u64> {
+        let mut activity = vec![0; monkey_count];
+        match self.cycle {
+            Some(Cycle { start, length }) if round_count > start => {
+                let (start, length) = (start as usize, length as usize);
+                let cycles = (round_count - start as u64) / length as u64;
+                let remainder = ((round_count - start as u64) % length as u64) as usize;
+                self.count(0..start, &mut activity, 1);
+                self.count(start..start + length, &mut activity, cycles);
+                self.count(start..start + remainder, &mut activity, 1);
+            }
+            _ => {
+                let simulated = round_count.min(self.inspections.len() as u64) as usize;
+                self.count(0..simulated, &mut activity, 1);
+            }
+        }
+
+        activity
+    }
+}
+
+pub fn item_histories(
+    monkeys: &[Monkey],
+    round_count: u64,
+    divide_worry_level: bool,
+) -> Result<Vec<ItemHistory>, WorryError> {
+    starting_items(monkeys)
+        .into_iter()
+        .enumerate()
+        .map(|(item, state)| {
+            ItemHistory::new(monkeys, item, state, round_count, divide_worry_level)
+        })
+        .collect()
+}
+
+pub fn fast_forward(
+    monkeys: &[Monkey],
+    round_count: u64,
+    divide_worry_level: bool,
+) -> Result<Vec<u64>, WorryError> {
+    let mut activity = vec![0; monkeys.len()];
+    for history in item_histories(monkeys, round_count, divide_worry_level)? {
+        for (total, count) in activity
+            .iter_mut()
+            .zip(history.activity(round_count, monkeys.len()))
+        {
+            *total += count;
+        }
+    }
+
+    Ok(activity)
+}
+
+pub fn trace(
+    monkeys: &[Monkey],
+    round_count: u64,
+    divide_worry_level: bool,
+) -> Result<Vec<Throw>, WorryError> {
+    let mut throws = vec![];
+    for (item, mut state) in starting_items(monkeys).into_iter().enumerate() {
+        for round in 0..round_count {
+            state = item_round(monkeys, item, round, state, divide_worry_level, &mut throws)?;
+        }
+    }
+
+    Ok(throws)
+}
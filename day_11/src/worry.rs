@@ -0,0 +1,217 @@
+use num::integer::gcd;
+use num::{BigUint, ToPrimitive, Zero};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum WorryError {
+    Overflow,
+    Negative,
+    ModulusOverflow,
+    DivisionByZero,
+    UnsupportedInResidueSpace(&'static str),
+    DivisorNotInModulus { divisor: u64, modulus: u64 },
+    MixedModes,
+}
+
+impl Display for WorryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorryError::Overflow => write!(f, "worry level overflowed"),
+            WorryError::Negative => write!(f, "worry level went negative"),
+            WorryError::ModulusOverflow => {
+                write!(f, "least common multiple of the divisors overflowed")
+            }
+            WorryError::DivisionByZero => write!(f, "division by zero"),
+            WorryError::UnsupportedInResidueSpace(operation) => {
+                write!(f, "{operation} is not defined on residues")
+            }
+            WorryError::DivisorNotInModulus { divisor, modulus } => {
+                write!(f, "cannot test divisibility by {divisor} modulo {modulus}")
+            }
+            WorryError::MixedModes => write!(f, "worry levels use different modes"),
+        }
+    }
+}
+
+impl Error for WorryError {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WorryMode {
+    Checked,
+    Unbounded,
+    Residue(u64),
+}
+
+impl WorryMode {
+    pub fn residue(divisors: &[u64]) -> Result<Self, WorryError> {
+        let mut modulus: u64 = 1;
+        for &divisor in divisors {
+            if divisor == 0 {
+                return Err(WorryError::DivisionByZero);
+            }
+            modulus = (modulus / gcd(modulus, divisor))
+                .checked_mul(divisor)
+                .ok_or(WorryError::ModulusOverflow)?;
+        }
+
+        Ok(WorryMode::Residue(modulus))
+    }
+
+    pub fn worry(self, value: u64) -> Worry {
+        match self {
+            WorryMode::Checked => Worry::Checked(value),
+            WorryMode::Unbounded => Worry::Unbounded(BigUint::from(value)),
+            WorryMode::Residue(modulus) => Worry::Residue {
+                value: value % modulus,
+                modulus,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Worry {
+    Checked(u64),
+    Unbounded(BigUint),
+    Residue { value: u64, modulus: u64 },
+}
+
+impl Worry {
+    pub fn mode(&self) -> WorryMode {
+        match self {
+            Worry::Checked(_) => WorryMode::Checked,
+            Worry::Unbounded(_) => WorryMode::Unbounded,
+            Worry::Residue { modulus, .. } => WorryMode::Residue(*modulus),
+        }
+    }
+
+    pub fn constant(&self, value: u64) -> Worry {
+        self.mode().worry(value)
+    }
+
+    pub fn value(&self) -> Result<u64, WorryError> {
+        match self {
+            Worry::Checked(value) => Ok(*value),
+            Worry::Unbounded(value) => value.to_u64().ok_or(WorryError::Overflow),
+            Worry::Residue { .. } => Err(WorryError::UnsupportedInResidueSpace(
+                "reading an exact value",
+            )),
+        }
+    }
+
+    pub fn add(&self, rhs: &Worry) -> Result<Worry, WorryError> {
+        match (self, rhs) {
+            (Worry::Checked(a), Worry::Checked(b)) => a
+                .checked_add(*b)
+                .map(Worry::Checked)
+                .ok_or(WorryError::Overflow),
+            (Worry::Unbounded(a), Worry::Unbounded(b)) => Ok(Worry::Unbounded(a + b)),
+            (Worry::Residue { value: a, modulus }, Worry::Residue { value: b, .. })
+                if self.mode() == rhs.mode() =>
+            {
+                Ok(Worry::Residue {
+                    value: ((*a as u128 + *b as u128) % *modulus as u128) as u64,
+                    modulus: *modulus,
+                })
+            }
+            _ => Err(WorryError::MixedModes),
+        }
+    }
+
+    pub fn sub(&self, rhs: &Worry) -> Result<Worry, WorryError> {
+        match (self, rhs) {
+            (Worry::Checked(a), Worry::Checked(b)) => a
+                .checked_sub(*b)
+                .map(Worry::Checked)
+                .ok_or(WorryError::Negative),
+            (Worry::Unbounded(a), Worry::Unbounded(b)) if a < b => Err(WorryError::Negative),
+            (Worry::Unbounded(a), Worry::Unbounded(b)) => Ok(Worry::Unbounded(a - b)),
+            (Worry::Residue { value: a, modulus }, Worry::Residue { value: b, .. })
+                if self.mode() == rhs.mode() =>
+            {
+                Ok(Worry::Residue {
+                    value: ((*a as u128 + *modulus as u128 - *b as u128) % *modulus as u128) as u64,
+                    modulus: *modulus,
+                })
+            }
+            _ => Err(WorryError::MixedModes),
+        }
+    }
+
+    pub fn mul(&self, rhs: &Worry) -> Result<Worry, WorryError> {
+        match (self, rhs) {
+            (Worry::Checked(a), Worry::Checked(b)) => a
+                .checked_mul(*b)
+                .map(Worry::Checked)
+                .ok_or(WorryError::Overflow),
+            (Worry::Unbounded(a), Worry::Unbounded(b)) => Ok(Worry::Unbounded(a * b)),
+            (Worry::Residue { value: a, modulus }, Worry::Residue { value: b, .. })
+                if self.mode() == rhs.mode() =>
+            {
+                Ok(Worry::Residue {
+                    value: ((*a as u128 * *b as u128) % *modulus as u128) as u64,
+                    modulus: *modulus,
+                })
+            }
+            _ => Err(WorryError::MixedModes),
+        }
+    }
+
+    pub fn div(&self, divisor: u64) -> Result<Worry, WorryError> {
+        if divisor == 0 {
+            return Err(WorryError::DivisionByZero);
+        }
+
+        match self {
+            Worry::Checked(value) => Ok(Worry::Checked(value / divisor)),
+            Worry::Unbounded(value) => Ok(Worry::Unbounded(value / divisor)),
+            Worry::Residue { .. } => Err(WorryError::UnsupportedInResidueSpace("division")),
+        }
+    }
+
+    pub fn rem(&self, divisor: u64) -> Result<Worry, WorryError> {
+        if divisor == 0 {
+            return Err(WorryError::DivisionByZero);
+        }
+
+        match self {
+            Worry::Checked(value) => Ok(Worry::Checked(value % divisor)),
+            Worry::Unbounded(value) => Ok(Worry::Unbounded(value % divisor)),
+            Worry::Residue { value, modulus } if modulus % divisor == 0 => Ok(Worry::Residue {
+                value: value % divisor,
+                modulus: *modulus,
+            }),
+            Worry::Residue { modulus, .. } => Err(WorryError::DivisorNotInModulus {
+                divisor,
+                modulus: *modulus,
+            }),
+        }
+    }
+
+    pub fn is_divisible_by(&self, divisor: u64) -> Result<bool, WorryError> {
+        if divisor == 0 {
+            return Err(WorryError::DivisionByZero);
+        }
+
+        match self {
+            Worry::Checked(value) => Ok(value % divisor == 0),
+            Worry::Unbounded(value) => Ok((value % divisor).is_zero()),
+            Worry::Residue { value, modulus } if modulus % divisor == 0 => Ok(value % divisor == 0),
+            Worry::Residue { modulus, .. } => Err(WorryError::DivisorNotInModulus {
+                divisor,
+                modulus: *modulus,
+            }),
+        }
+    }
+}
+
+impl Display for Worry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Worry::Checked(value) => write!(f, "{value}"),
+            Worry::Unbounded(value) => write!(f, "{value}"),
+            Worry::Residue { value, .. } => write!(f, "{value}"),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+pub trait ClimbingRule {
+    fn step_cost(&self, from: u32, to: u32) -> Option<u64>;
+
+    fn has_uniform_cost(&self) -> bool {
+        false
+    }
+}
+
+pub struct Limits {
+    pub max_climb: u32,
+    pub max_descent: u32,
+}
+
+impl Limits {
+    pub const STANDARD: Limits = Limits {
+        max_climb: 1,
+        max_descent: u32::MAX,
+    };
+
+    fn allows(&self, from: u32, to: u32) -> bool {
+        to <= from.saturating_add(self.max_climb) && from <= to.saturating_add(self.max_descent)
+    }
+}
+
+impl ClimbingRule for Limits {
+    fn step_cost(&self, from: u32, to: u32) -> Option<u64> {
+        self.allows(from, to).then_some(1)
+    }
+
+    fn has_uniform_cost(&self) -> bool {
+        true
+    }
+}
+
+pub struct Effort {
+    pub limits: Limits,
+    pub flat: u64,
+    pub per_climb: u64,
+    pub per_descent: u64,
+}
+
+impl ClimbingRule for Effort {
+    fn step_cost(&self, from: u32, to: u32) -> Option<u64> {
+        if !self.limits.allows(from, to) {
+            return None;
+        }
+
+        let climb = to.saturating_sub(from) as u64;
+        let descent = from.saturating_sub(to) as u64;
+        Some(self.flat + climb * self.per_climb + descent * self.per_descent)
+    }
+}
@@ -1,54 +1,161 @@
-use std::collections::{HashSet, VecDeque};
+use crate::climbing::{ClimbingRule, Effort, Limits};
+use crate::search::{
+    distance_field, find_path, height, render_cells, shortest_path_cells, Path, Position,
+};
+use std::collections::HashMap;
 use std::fs::read_to_string;
+use std::str::FromStr;
 
-#[derive(Copy, Clone)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+mod climbing;
+mod search;
+
+fn main() {
+    let input = read_to_string("input").unwrap();
+    let (grid, start, end) = process_input(&input);
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let (command, options) = match args.as_slice() {
+        [command @ ("path" | "fields"), options @ ..] => (*command, options),
+        options => ("", options),
+    };
+    let Some(rule) = get_rule(options) else {
+        eprintln!(
+            "usage: day_12 [path | fields] [climb <n>] [descent <n>] [cost <flat> <per climb> <per descent>]"
+        );
+        return;
+    };
+
+    let path_1 = find_path(
+        &grid,
+        start,
+        |position| position == end,
+        rule.as_ref(),
+        false,
+    );
+    let path_2 = find_path(
+        &grid,
+        end,
+        |position| height(&grid, position) == 0,
+        rule.as_ref(),
+        true,
+    );
+
+    let describe = |path: &Option<Path>| match path {
+        Some(path) => path.cost.to_string(),
+        None => "unreachable".to_string(),
+    };
+    println!(
+        "part 1: {} part 2: {}",
+        describe(&path_1),
+        describe(&path_2)
+    );
+
+    let labels = HashMap::from([(start, 'S'), (end, 'E')]);
+    match command {
+        "path" => {
+            for path in [path_1, path_2].into_iter().flatten() {
+                println!("\n{} steps:\n{}", path.steps(), path.render(&grid, &labels));
+            }
+        }
+        "fields" => analyse(&grid, start, end, rule.as_ref(), &labels),
+        _ => {}
+    }
 }
 
-#[derive(Hash, Eq, PartialEq, Copy, Clone, Debug)]
-struct Position {
-    x: usize,
-    y: usize,
+fn analyse(
+    grid: &[Vec<u32>],
+    start: Position,
+    end: Position,
+    rule: &dyn ClimbingRule,
+    labels: &HashMap<Position, char>,
+) {
+    let to_end = distance_field(grid, &[end], rule, true);
+    let (farthest, distance) = to_end
+        .positions()
+        .filter_map(|position| Some((position, to_end.get(position)?)))
+        .max_by_key(|&(_, distance)| distance)
+        .unwrap();
+    println!(
+        "\nfarthest cell that can reach E: ({}, {}) at {distance}",
+        farthest.x, farthest.y
+    );
+
+    let lowest: Vec<Position> = to_end
+        .positions()
+        .filter(|&position| height(grid, position) == 0)
+        .collect();
+    let lowest_distances: Vec<u64> = lowest.iter().filter_map(|&a| to_end.get(a)).collect();
+    match (lowest_distances.iter().min(), lowest_distances.iter().max()) {
+        (Some(min), Some(max)) => println!(
+            "{} of {} lowest cells can reach E, in {min} to {max} steps",
+            lowest_distances.len(),
+            lowest.len()
+        ),
+        _ => println!("none of the {} lowest cells can reach E", lowest.len()),
+    }
+
+    let from_end = distance_field(grid, &[end], rule, false);
+    let reachable: Vec<(Position, u64)> = from_end
+        .positions()
+        .filter_map(|position| Some((position, from_end.get(position)?)))
+        .collect();
+    let (farthest, distance) = reachable
+        .iter()
+        .copied()
+        .max_by_key(|&(_, distance)| distance)
+        .unwrap();
+    println!(
+        "{} cells can be reached from E, the farthest is ({}, {}) at {distance}",
+        reachable.len(),
+        farthest.x,
+        farthest.y
+    );
+
+    let unreachable = distance_field(grid, &[start], rule, false).unreachable();
+    println!("{} cells are unreachable from S", unreachable.len());
+
+    let cells = shortest_path_cells(grid, start, end, rule);
+    println!(
+        "{} cells lie on a shortest path from S to E:\n{}",
+        cells.len(),
+        render_cells(grid, &cells, labels)
+    );
 }
 
-impl Position {
-    fn step(&self, direction: Direction, grid: &[Vec<u32>], is_backwards: bool) -> Option<Self> {
-        let (dx, dy) = match direction {
-            Direction::Up => (0, -1),
-            Direction::Down => (0, 1),
-            Direction::Left => (-1, 0),
-            Direction::Right => (1, 0),
-        };
-
-        let new_x: usize = (self.x as i32 + dx).try_into().ok()?;
-        let new_y: usize = (self.y as i32 + dy).try_into().ok()?;
-
-        let &new_height = grid.get(new_y)?.get(new_x)?;
-        let &current_height = grid.get(self.y)?.get(self.x)?;
-        let valid = if !is_backwards {
-            new_height <= current_height + 1
-        } else {
-            current_height <= new_height + 1
-        };
-
-        if valid {
-            Some(Position { x: new_x, y: new_y })
-        } else {
-            None
+fn get_rule(options: &[&str]) -> Option<Box<dyn ClimbingRule>> {
+    let mut limits = Limits::STANDARD;
+    let mut cost = None;
+
+    let mut options = options.iter();
+    while let Some(&option) = options.next() {
+        match option {
+            "climb" => limits.max_climb = next_value(&mut options)?,
+            "descent" => limits.max_descent = next_value(&mut options)?,
+            "cost" => {
+                cost = Some((
+                    next_value(&mut options)?,
+                    next_value(&mut options)?,
+                    next_value(&mut options)?,
+                ))
+            }
+            _ => return None,
         }
     }
+
+    Some(match cost {
+        Some((flat, per_climb, per_descent)) => Box::new(Effort {
+            limits,
+            flat,
+            per_climb,
+            per_descent,
+        }),
+        None => Box::new(limits),
+    })
 }
-fn main() {
-    let input = read_to_string("input").unwrap();
-    let (grid, start, end) = process_input(&input);
 
-    let output_1 = bfs(&grid, start, end, false);
-    let output_2 = bfs(&grid, start, end, true);
-    println!("part 1: {output_1} part 2: {output_2}");
+fn next_value<'a, T: FromStr>(options: &mut impl Iterator<Item = &'a &'a str>) -> Option<T> {
+    options.next()?.parse().ok()
 }
 
 fn process_input(input: &str) -> (Vec<Vec<u32>>, Position, Position) {
@@ -63,40 +170,6 @@ fn process_input(input: &str) -> (Vec<Vec<u32>>, Position, Position) {
     (grid, start, end)
 }
 
-fn bfs(grid: &[Vec<u32>], start: Position, end: Position, is_backwards: bool) -> u32 {
-    let mut frontier = if !is_backwards {
-        VecDeque::from([(start, 0)])
-    } else {
-        VecDeque::from([(end, 0)])
-    };
-    let mut visited: HashSet<Position> = HashSet::new();
-    while let Some((position, distance)) = frontier.pop_front() {
-        if !visited.insert(position) {
-            continue;
-        }
-
-        if !is_backwards && position == end {
-            return distance;
-        }
-
-        if grid[position.y][position.x] == 0 && is_backwards {
-            return distance;
-        }
-
-        [
-            Direction::Up,
-            Direction::Down,
-            Direction::Left,
-            Direction::Right,
-        ]
-        .into_iter()
-        .filter_map(|d| position.step(d, grid, is_backwards))
-        .for_each(|next| frontier.push_back((next, distance + 1)));
-    }
-    println!("{:?}", end);
-    panic!("end not reachable")
-}
-
 fn get_height(c: char) -> u32 {
     match c {
         'S' => 0,
@@ -114,3 +187,92 @@ fn find_char(input: &str, c: char) -> Position {
 
     Position { x, y }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::DistanceField;
+
+    const SAMPLE: &str = include_str!("../practice");
+
+    fn distances(field: &DistanceField) -> Vec<Vec<Option<u64>>> {
+        let mut rows: Vec<Vec<Option<u64>>> = vec![];
+        for position in field.positions() {
+            if position.x == 0 {
+                rows.push(vec![]);
+            }
+            rows.last_mut().unwrap().push(field.get(position));
+        }
+        rows
+    }
+
+    #[test]
+    fn distance_fields_run_both_ways() {
+        let (grid, start, end) = process_input(SAMPLE);
+        let rule = Limits::STANDARD;
+
+        let from_start = distance_field(&grid, &[start], &rule, false);
+        let to_end = distance_field(&grid, &[end], &rule, true);
+        assert_eq!(from_start.get(end), Some(31));
+        assert_eq!(to_end.get(start), Some(31));
+        assert_eq!(to_end.get(end), Some(0));
+
+        let from_end = distance_field(&grid, &[end], &rule, false);
+        assert_eq!(from_end.get(start), Some(7));
+        assert!(from_end.unreachable().is_empty());
+
+        let lowest: Vec<Position> = to_end
+            .positions()
+            .filter(|&position| height(&grid, position) == 0)
+            .collect();
+        let from_lowest = distance_field(&grid, &lowest, &rule, false);
+        assert_eq!(from_lowest.get(end), Some(29));
+        assert_eq!(
+            lowest
+                .iter()
+                .filter_map(|&position| to_end.get(position))
+                .min(),
+            Some(29)
+        );
+    }
+
+    #[test]
+    fn lists_cells_unreachable_from_the_start() {
+        let (grid, start, end) = process_input("Sbz\nazE\n");
+        let from_start = distance_field(&grid, &[start], &Limits::STANDARD, false);
+        assert_eq!(
+            distances(&from_start),
+            [[Some(0), Some(1), None], [Some(1), None, None]]
+        );
+        assert_eq!(
+            from_start.unreachable(),
+            [Position { x: 2, y: 0 }, Position { x: 1, y: 1 }, end]
+        );
+
+        let (grid, start, _) = process_input(SAMPLE);
+        let from_start = distance_field(&grid, &[start], &Limits::STANDARD, false);
+        assert!(from_start.unreachable().is_empty());
+    }
+
+    #[test]
+    fn finds_every_cell_on_a_shortest_path() {
+        let (grid, start, end) = process_input(SAMPLE);
+        let rule = Limits::STANDARD;
+        let cells = shortest_path_cells(&grid, start, end, &rule);
+        let labels = HashMap::from([(start, 'S'), (end, 'E')]);
+        assert_eq!(
+            render_cells(&grid, &cells, &labels),
+            "S#######\n########\na####E##\na#######\nab######\n"
+        );
+
+        let from_start = distance_field(&grid, &[start], &rule, false);
+        let to_end = distance_field(&grid, &[end], &rule, true);
+        assert!(cells.contains(&start) && cells.contains(&end));
+        assert!(cells
+            .iter()
+            .all(|&cell| from_start.get(cell).unwrap() + to_end.get(cell).unwrap() == 31));
+
+        let (grid, start, end) = process_input("Sbz\nazE\n");
+        assert!(shortest_path_cells(&grid, start, end, &rule).is_empty());
+    }
+}
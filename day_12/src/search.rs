@@ -0,0 +1,312 @@
+use crate::climbing::ClimbingRule;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+#[derive(Copy, Clone)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    fn offset(self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+
+    fn arrow(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+            Direction::Right => '>',
+        }
+    }
+}
+
+#[derive(Hash, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Debug)]
+pub struct Position {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Position {
+    pub fn step(&self, direction: Direction, grid: &[Vec<u32>]) -> Option<Self> {
+        let (dx, dy) = direction.offset();
+        let new_x: usize = (self.x as i32 + dx).try_into().ok()?;
+        let new_y: usize = (self.y as i32 + dy).try_into().ok()?;
+        grid.get(new_y)?.get(new_x)?;
+
+        Some(Position { x: new_x, y: new_y })
+    }
+
+    fn direction_to(&self, other: Position) -> Option<Direction> {
+        Direction::ALL.into_iter().find(|&direction| {
+            let (dx, dy) = direction.offset();
+            self.x as i32 + dx == other.x as i32 && self.y as i32 + dy == other.y as i32
+        })
+    }
+}
+
+pub fn height(grid: &[Vec<u32>], position: Position) -> u32 {
+    grid[position.y][position.x]
+}
+
+pub fn neighbours<'a>(
+    grid: &'a [Vec<u32>],
+    position: Position,
+    rule: &'a dyn ClimbingRule,
+    is_backwards: bool,
+) -> impl Iterator<Item = (Position, u64)> + 'a {
+    Direction::ALL.into_iter().filter_map(move |direction| {
+        let next = position.step(direction, grid)?;
+        let (from, to) = if is_backwards {
+            (next, position)
+        } else {
+            (position, next)
+        };
+        let cost = rule.step_cost(height(grid, from), height(grid, to))?;
+        Some((next, cost))
+    })
+}
+
+pub struct Path {
+    pub cost: u64,
+    pub positions: Vec<Position>,
+}
+
+impl Path {
+    pub fn steps(&self) -> usize {
+        self.positions.len() - 1
+    }
+
+    pub fn render(&self, grid: &[Vec<u32>], labels: &HashMap<Position, char>) -> String {
+        let mut rows = label_rows(grid, labels);
+        for pair in self.positions.windows(2) {
+            if let Some(direction) = pair[0].direction_to(pair[1]) {
+                rows[pair[0].y][pair[0].x] = direction.arrow();
+            }
+        }
+
+        join_rows(rows)
+    }
+}
+
+fn label_rows(grid: &[Vec<u32>], labels: &HashMap<Position, char>) -> Vec<Vec<char>> {
+    grid.iter()
+        .enumerate()
+        .map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(x, &height)| {
+                    labels
+                        .get(&Position { x, y })
+                        .copied()
+                        .unwrap_or((b'a' + height as u8) as char)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn join_rows(rows: Vec<Vec<char>>) -> String {
+    rows.into_iter()
+        .map(|row| row.into_iter().collect::<String>() + "\n")
+        .collect()
+}
+
+fn reconstruct(
+    parents: &HashMap<Position, Position>,
+    mut position: Position,
+    cost: u64,
+    is_backwards: bool,
+) -> Path {
+    let mut positions = vec![position];
+    while let Some(&parent) = parents.get(&position) {
+        positions.push(parent);
+        position = parent;
+    }
+
+    if !is_backwards {
+        positions.reverse();
+    }
+
+    Path { cost, positions }
+}
+
+fn bfs(
+    grid: &[Vec<u32>],
+    start: Position,
+    is_goal: impl Fn(Position) -> bool,
+    rule: &dyn ClimbingRule,
+    is_backwards: bool,
+) -> Option<Path> {
+    let mut frontier = VecDeque::from([(start, 0)]);
+    let mut parents: HashMap<Position, Position> = HashMap::new();
+    let mut visited: HashSet<Position> = HashSet::from([start]);
+    while let Some((position, distance)) = frontier.pop_front() {
+        if is_goal(position) {
+            return Some(reconstruct(&parents, position, distance, is_backwards));
+        }
+
+        for (next, _) in neighbours(grid, position, rule, is_backwards) {
+            if visited.insert(next) {
+                parents.insert(next, position);
+                frontier.push_back((next, distance + 1));
+            }
+        }
+    }
+
+    None
+}
+
+fn dijkstra(
+    grid: &[Vec<u32>],
+    start: Position,
+    is_goal: impl Fn(Position) -> bool,
+    rule: &dyn ClimbingRule,
+    is_backwards: bool,
+) -> Option<Path> {
+    let mut frontier = BinaryHeap::from([Reverse((0, start))]);
+    let mut parents: HashMap<Position, Position> = HashMap::new();
+    let mut costs: HashMap<Position, u64> = HashMap::from([(start, 0)]);
+    while let Some(Reverse((cost, position))) = frontier.pop() {
+        if costs[&position] < cost {
+            continue;
+        }
+
+        if is_goal(position) {
+            return Some(reconstruct(&parents, position, cost, is_backwards));
+        }
+
+        for (next, step_cost) in neighbours(grid, position, rule, is_backwards) {
+            let next_cost = cost + step_cost;
+            if costs.get(&next).is_none_or(|&known| next_cost < known) {
+                costs.insert(next, next_cost);
+                parents.insert(next, position);
+                frontier.push(Reverse((next_cost, next)));
+            }
+        }
+    }
+
+    None
+}
+
+pub fn find_path(
+    grid: &[Vec<u32>],
+    start: Position,
+    is_goal: impl Fn(Position) -> bool,
+    rule: &dyn ClimbingRule,
+    is_backwards: bool,
+) -> Option<Path> {
+    if rule.has_uniform_cost() {
+        bfs(grid, start, is_goal, rule, is_backwards)
+    } else {
+        dijkstra(grid, start, is_goal, rule, is_backwards)
+    }
+}
+
+pub struct DistanceField {
+    costs: Vec<Vec<Option<u64>>>,
+}
+
+impl DistanceField {
+    pub fn get(&self, position: Position) -> Option<u64> {
+        self.costs[position.y][position.x]
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.costs
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| (0..row.len()).map(move |x| Position { x, y }))
+    }
+
+    pub fn unreachable(&self) -> Vec<Position> {
+        self.positions()
+            .filter(|&position| self.get(position).is_none())
+            .collect()
+    }
+}
+
+pub fn distance_field(
+    grid: &[Vec<u32>],
+    sources: &[Position],
+    rule: &dyn ClimbingRule,
+    is_backwards: bool,
+) -> DistanceField {
+    let mut costs: Vec<Vec<Option<u64>>> = grid.iter().map(|row| vec![None; row.len()]).collect();
+    let mut frontier = BinaryHeap::new();
+    for &source in sources {
+        costs[source.y][source.x] = Some(0);
+        frontier.push(Reverse((0, source)));
+    }
+
+    while let Some(Reverse((cost, position))) = frontier.pop() {
+        if costs[position.y][position.x].is_some_and(|known| known < cost) {
+            continue;
+        }
+
+        for (next, step_cost) in neighbours(grid, position, rule, is_backwards) {
+            let next_cost = cost + step_cost;
+            let known = &mut costs[next.y][next.x];
+            if known.is_none_or(|known| next_cost < known) {
+                *known = Some(next_cost);
+                frontier.push(Reverse((next_cost, next)));
+            }
+        }
+    }
+
+    DistanceField { costs }
+}
+
+pub fn shortest_path_cells(
+    grid: &[Vec<u32>],
+    start: Position,
+    end: Position,
+    rule: &dyn ClimbingRule,
+) -> HashSet<Position> {
+    let from_start = distance_field(grid, &[start], rule, false);
+    let to_end = distance_field(grid, &[end], rule, true);
+    let Some(total) = from_start.get(end) else {
+        return HashSet::new();
+    };
+
+    from_start
+        .positions()
+        .filter(|&position| {
+            matches!(
+                (from_start.get(position), to_end.get(position)),
+                (Some(a), Some(b)) if a + b == total
+            )
+        })
+        .collect()
+}
+
+pub fn render_cells(
+    grid: &[Vec<u32>],
+    cells: &HashSet<Position>,
+    labels: &HashMap<Position, char>,
+) -> String {
+    let mut rows = label_rows(grid, labels);
+    for cell in cells {
+        if !labels.contains_key(cell) {
+            rows[cell.y][cell.x] = '#';
+        }
+    }
+
+    join_rows(rows)
+}
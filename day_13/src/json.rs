@@ -0,0 +1,89 @@
+use crate::packet::Packet;
+use serde_json::Value;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub enum JsonError {
+    NotAnInteger(String),
+    Unsupported(&'static str),
+    NotAPacketList,
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JsonError::NotAnInteger(number) => write!(f, "{number} is not an integer"),
+            JsonError::Unsupported(kind) => write!(f, "{kind} cannot appear in a packet"),
+            JsonError::NotAPacketList => write!(f, "expected an array of packets"),
+        }
+    }
+}
+
+impl Error for JsonError {}
+
+impl From<&Packet> for Value {
+    fn from(packet: &Packet) -> Self {
+        match packet {
+            Packet::Integer(x) => Value::Number(x.to_string().parse().unwrap()),
+            Packet::List(packets) => Value::Array(packets.iter().map(Value::from).collect()),
+        }
+    }
+}
+
+impl TryFrom<&Value> for Packet {
+    type Error = JsonError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Array(values) => Ok(Packet::List(
+                values
+                    .iter()
+                    .map(Packet::try_from)
+                    .collect::<Result<_, _>>()?,
+            )),
+            Value::Number(number) => number
+                .to_string()
+                .parse()
+                .map(Packet::Integer)
+                .map_err(|_| JsonError::NotAnInteger(number.to_string())),
+            Value::Null => Err(JsonError::Unsupported("null")),
+            Value::Bool(_) => Err(JsonError::Unsupported("a boolean")),
+            Value::String(_) => Err(JsonError::Unsupported("a string")),
+            Value::Object(_) => Err(JsonError::Unsupported("an object")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck::quickcheck;
+
+    fn from_text(text: &str) -> Result<Packet, JsonError> {
+        Packet::try_from(&serde_json::from_str::<Value>(text).unwrap())
+    }
+
+    quickcheck! {
+        fn json_round_trips(packet: Packet) -> bool {
+            let text = Value::from(&packet).to_string();
+            text == packet.to_string() && from_text(&text).is_ok_and(|p| p.is_identical(&packet))
+        }
+    }
+
+    #[test]
+    fn keeps_large_integers_exact() {
+        let text = "[123456789012345678901234567890,[-98765432109876543210]]";
+        assert_eq!(from_text(text).unwrap().to_string(), text);
+    }
+
+    #[test]
+    fn rejects_values_that_are_not_packets() {
+        let error = |text| from_text(text).unwrap_err().to_string();
+        assert_eq!(error("[1,[2.5]]"), "2.5 is not an integer");
+        assert_eq!(error("[null]"), "null cannot appear in a packet");
+        assert_eq!(error("[[true]]"), "a boolean cannot appear in a packet");
+        assert_eq!(error("[\"1\"]"), "a string cannot appear in a packet");
+        assert_eq!(error("{}"), "an object cannot appear in a packet");
+    }
+}
@@ -1,143 +1,122 @@
+use crate::json::JsonError;
+use crate::packet::{parse, Packet, ParseError};
 use itertools::{chain, Itertools};
-use std::cmp::Ordering;
+use serde_json::Value;
+use std::error::Error;
 use std::fs::read_to_string;
 
-#[derive(Clone)]
-enum Token {
-    OpenBracket,
-    CloseBracket,
-    Integer(u32),
-}
+mod json;
+mod packet;
 
-struct Tokens(Vec<Token>);
-
-impl<T: Into<String>> From<T> for Tokens {
-    fn from(value: T) -> Self {
-        let value = value.into();
-        Tokens({
-            value
-                .split(',')
-                .flat_map(|s| {
-                    let open_bracket_count = s.chars().take_while(|&c| c == '[').count();
-                    let close_bracket_count = s.chars().skip_while(|&c| c != ']').count();
-
-                    let value = if s.trim_matches(|c| c == '[' || c == ']').is_empty() {
-                        vec![]
-                    } else {
-                        vec![Token::Integer(
-                            s.trim_matches(|c| c == '[' || c == ']').parse().unwrap(),
-                        )]
-                    };
-
-                    chain!(
-                        vec![Token::OpenBracket; open_bracket_count],
-                        value,
-                        vec![Token::CloseBracket; close_bracket_count]
-                    )
-                })
-                .collect()
-        })
-    }
-}
-
-#[derive(Eq, Clone)]
-enum Packet {
-    Integer(u32),
-    List(Vec<Packet>),
-}
+fn main() {
+    let input = read_to_string("input").unwrap();
 
-impl From<Tokens> for Packet {
-    fn from(Tokens(value): Tokens) -> Self {
-        let mut packets: Vec<Packet> = vec![];
-        for token in value {
-            match token {
-                Token::Integer(x) => {
-                    if let Packet::List(v) = packets.last_mut().unwrap() {
-                        v.push(Packet::Integer(x))
-                    } else {
-                        panic!("Integer packet on stack")
-                    }
-                }
-
-                Token::OpenBracket => packets.push(Packet::new()),
-
-                Token::CloseBracket => {
-                    let finished_packet = packets.pop().unwrap();
-                    match packets.last_mut() {
-                        None => return finished_packet,
-
-                        Some(Packet::List(v)) => v.push(finished_packet),
-
-                        _ => {
-                            panic!("Integer packet on stack")
-                        }
-                    };
-                }
-            }
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args
+        .iter()
+        .map(|s| s.as_str())
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [] => run(&input),
+        ["sort", path] => sort(path),
+        ["explain", pair] if pair.parse::<usize>().is_ok() => {
+            explain_pair(&input, pair.parse().unwrap())
         }
+        ["explain", left, right] => explain(left, right),
+        ["key", dividers @ ..] if !dividers.is_empty() => decoder_key(&input, dividers),
+        ["json"] => to_json(&input),
+        ["from-json", path] => from_json(path),
+        _ => {
+            eprintln!(
+                "usage: day_13 [sort <file> | explain <pair> | explain <left> <right> | key <divider>... | json | from-json <file>]"
+            );
+            return;
+        }
+    };
 
-        packets.into_iter().next().unwrap()
+    if let Err(error) = result {
+        eprintln!("{error}");
     }
 }
 
-impl<T: Into<String>> From<T> for Packet {
-    fn from(value: T) -> Self {
-        Packet::from(Tokens::from(value))
-    }
-}
+fn run(input: &str) -> Result<(), Box<dyn Error>> {
+    let packets = get_packets(input)?;
+    let pairs = get_packet_pairs(&packets);
 
-impl Ord for Packet {
-    fn cmp(&self, other: &Self) -> Ordering {
-        match (self, other) {
-            (Packet::Integer(x), Packet::Integer(y)) => x.cmp(y),
-            (Packet::List(v1), Packet::List(v2)) => v1.cmp(v2),
-            (Packet::Integer(x), Packet::List(v)) => vec![Packet::Integer(*x)].cmp(v),
-            (p1, p2) => p2.cmp(p1).reverse(),
-        }
-    }
+    let output_1 = get_unordered_indices(pairs);
+    let output_2 = get_decoder_key(packets, &get_default_dividers());
+
+    println!("part 1: {output_1} part 2: {output_2}");
+    Ok(())
 }
 
-impl PartialOrd for Packet {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+fn sort(path: &str) -> Result<(), Box<dyn Error>> {
+    for packet in get_packets(&read_to_string(path)?)?.into_iter().sorted() {
+        println!("{packet}");
     }
+    Ok(())
 }
 
-impl PartialEq for Packet {
-    fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (Packet::Integer(x), Packet::Integer(y)) => x.eq(y),
-            (Packet::List(v1), Packet::List(v2)) => v1.eq(v2),
-            (Packet::Integer(x), Packet::List(v)) => vec![Packet::Integer(*x)].eq(v),
-            (p1, p2) => p2.eq(p1),
-        }
+fn explain_pair(input: &str, pair: usize) -> Result<(), Box<dyn Error>> {
+    let pairs = get_packet_pairs(&get_packets(input)?);
+    match pair.checked_sub(1).and_then(|index| pairs.get(index)) {
+        Some((left, right)) => println!("== Pair {pair} ==\n{}", left.explain(right)),
+        None => eprintln!("there are only {} pairs", pairs.len()),
     }
+    Ok(())
 }
 
-impl Packet {
-    fn new() -> Self {
-        Packet::List(vec![])
-    }
+fn explain(left: &str, right: &str) -> Result<(), Box<dyn Error>> {
+    let left: Packet = left.parse()?;
+    let right: Packet = right.parse()?;
+    println!("{}", left.explain(&right));
+    Ok(())
 }
 
-fn main() {
-    let input = read_to_string("input").unwrap();
+fn decoder_key(input: &str, dividers: &[&str]) -> Result<(), Box<dyn Error>> {
+    let dividers: Vec<Packet> = dividers
+        .iter()
+        .map(|divider| divider.parse())
+        .collect::<Result<_, _>>()?;
+    let key = get_decoder_key(get_packets(input)?, &dividers);
+    println!("decoder key: {key}");
+    Ok(())
+}
 
-    let pairs = get_packet_pairs(&input);
-    let packets = get_packets(&input);
+fn to_json(input: &str) -> Result<(), Box<dyn Error>> {
+    let packets = get_packets(input)?;
+    let json = Value::Array(packets.iter().map(Value::from).collect());
+    println!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(())
+}
 
-    let output_1 = get_unordered_indices(pairs);
-    let output_2 = get_decoder_key(packets);
+fn from_json(path: &str) -> Result<(), Box<dyn Error>> {
+    let json: Value = serde_json::from_str(&read_to_string(path)?)?;
+    let Value::Array(values) = json else {
+        return Err(JsonError::NotAPacketList.into());
+    };
+    let packets = values
+        .iter()
+        .map(Packet::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    for packet in packets {
+        println!("{packet}");
+    }
+    Ok(())
+}
 
-    println!("part 1: {output_1} part 2: {output_2}");
+fn get_default_dividers() -> Vec<Packet> {
+    vec!["[[2]]".parse().unwrap(), "[[6]]".parse().unwrap()]
 }
 
-fn get_decoder_key(packets: Vec<Packet>) -> usize {
-    let divider_packets = ["[[2]]".into(), "[[6]]".into()];
-    chain!(packets, divider_packets.clone())
-        .sorted()
+fn get_decoder_key(packets: Vec<Packet>, divider_packets: &[Packet]) -> usize {
+    let packets = packets.into_iter().map(|packet| (packet, false));
+    let dividers = divider_packets.iter().map(|packet| (packet.clone(), true));
+    chain!(packets, dividers)
+        .sorted_by(|(p1, _), (p2, _)| p1.cmp(p2))
         .enumerate()
-        .filter(|(_, p)| divider_packets.contains(p))
+        .filter(|(_, (_, is_divider))| *is_divider)
         .map(|(i, _)| i + 1)
         .product()
 }
@@ -149,20 +128,41 @@ fn get_unordered_indices(pairs: Vec<(Packet, Packet)>) -> usize {
         .map(|(i, _)| i + 1)
         .sum()
 }
-fn get_packet_pairs(input: &str) -> Vec<(Packet, Packet)> {
-    input
-        .split("\n\n")
-        .map(|s| {
-            let lines: Vec<&str> = s.lines().collect();
-            (lines[0].into(), lines[1].into())
-        })
+fn get_packet_pairs(packets: &[Packet]) -> Vec<(Packet, Packet)> {
+    packets
+        .chunks_exact(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
         .collect()
 }
 
-fn get_packets(input: &str) -> Vec<Packet> {
+fn get_packets(input: &str) -> Result<Vec<Packet>, ParseError> {
     input
         .lines()
-        .filter(|s| !s.is_empty())
-        .map(|s| s.into())
+        .enumerate()
+        .filter(|(_, s)| !s.trim().is_empty())
+        .map(|(i, s)| parse(s, i + 1))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = include_str!("../practice");
+
+    fn key_with(dividers: &[&str]) -> usize {
+        let dividers: Vec<Packet> = dividers.iter().map(|d| d.parse().unwrap()).collect();
+        get_decoder_key(get_packets(SAMPLE).unwrap(), &dividers)
+    }
+
+    #[test]
+    fn decoder_key_uses_the_given_dividers() {
+        assert_eq!(
+            get_decoder_key(get_packets(SAMPLE).unwrap(), &get_default_dividers()),
+            140
+        );
+        assert_eq!(key_with(&["[[2]]"]), 10);
+        // dividers sort after packets they equal, so [] lands second and [[[9]]] after [9]
+        assert_eq!(key_with(&["[]", "[[10]]", "[[[9]]]"]), 2 * 18 * 19);
+    }
+}
@@ -0,0 +1,526 @@
+use num::BigInt;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+const MAX_DEPTH: usize = 1000;
+
+#[derive(Eq, Clone, Debug)]
+pub enum Packet {
+    Integer(BigInt),
+    List(Vec<Packet>),
+}
+
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Packet::Integer(x), Packet::Integer(y)) => x.cmp(y),
+            (Packet::List(v1), Packet::List(v2)) => v1.cmp(v2),
+            (Packet::Integer(x), Packet::List(v)) => {
+                std::slice::from_ref(&Packet::Integer(x.clone())).cmp(v.as_slice())
+            }
+            (p1, p2) => p2.cmp(p1).reverse(),
+        }
+    }
+}
+
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Packet {
+    pub fn is_identical(&self, other: &Packet) -> bool {
+        match (self, other) {
+            (Packet::Integer(x), Packet::Integer(y)) => x == y,
+            (Packet::List(v1), Packet::List(v2)) => {
+                v1.len() == v2.len() && v1.iter().zip(v2).all(|(p1, p2)| p1.is_identical(p2))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Display for Packet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Packet::Integer(x) => write!(f, "{x}"),
+            Packet::List(packets) => {
+                write!(f, "[")?;
+                for (i, packet) in packets.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{packet}")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    fn name(self) -> &'static str {
+        match self {
+            Side::Left => "left",
+            Side::Right => "right",
+        }
+    }
+
+    fn capitalised_name(self) -> &'static str {
+        match self {
+            Side::Left => "Left",
+            Side::Right => "Right",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Step {
+    Compare { left: Packet, right: Packet },
+    Promote { side: Side, packet: Packet },
+    Smaller(Side),
+    RanOut(Side),
+}
+
+pub struct Explanation {
+    pub ordering: Ordering,
+    pub steps: Vec<(usize, Step)>,
+    pub deciding_path: Vec<usize>,
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let verdict = match self.ordering {
+            Ordering::Less => "in the right order",
+            Ordering::Greater => "not in the right order",
+            Ordering::Equal => "equal",
+        };
+
+        for (depth, step) in &self.steps {
+            write!(f, "{:width$}- ", "", width = depth * 2)?;
+            match step {
+                Step::Compare { left, right } => writeln!(f, "Compare {left} vs {right}")?,
+                Step::Promote { side, packet } => writeln!(
+                    f,
+                    "Mixed types; convert {} to {packet} and retry comparison",
+                    side.name()
+                )?,
+                Step::Smaller(side) => writeln!(
+                    f,
+                    "{} side is smaller, so inputs are {verdict}",
+                    side.capitalised_name()
+                )?,
+                Step::RanOut(side) => writeln!(
+                    f,
+                    "{} side ran out of items, so inputs are {verdict}",
+                    side.capitalised_name()
+                )?,
+            }
+        }
+
+        match self.ordering {
+            Ordering::Equal => write!(f, "packets are equal"),
+            _ => write!(f, "decided at element path {:?}", self.deciding_path),
+        }
+    }
+}
+
+impl Packet {
+    pub fn explain(&self, other: &Packet) -> Explanation {
+        let mut explanation = Explanation {
+            ordering: Ordering::Equal,
+            steps: vec![],
+            deciding_path: vec![],
+        };
+        explanation.ordering = explain_into(self, other, 0, &mut explanation);
+        explanation
+    }
+}
+
+fn explain_into(
+    left: &Packet,
+    right: &Packet,
+    depth: usize,
+    explanation: &mut Explanation,
+) -> Ordering {
+    explanation.steps.push((
+        depth,
+        Step::Compare {
+            left: left.clone(),
+            right: right.clone(),
+        },
+    ));
+
+    let decide = |explanation: &mut Explanation, ordering: Ordering, step: Step| {
+        if ordering != Ordering::Equal {
+            explanation.steps.push((depth + 1, step));
+        }
+        ordering
+    };
+
+    match (left, right) {
+        (Packet::Integer(x), Packet::Integer(y)) => {
+            let ordering = x.cmp(y);
+            let side = if ordering == Ordering::Less {
+                Side::Left
+            } else {
+                Side::Right
+            };
+            decide(explanation, ordering, Step::Smaller(side))
+        }
+        (Packet::List(v1), Packet::List(v2)) => {
+            for (i, (p1, p2)) in v1.iter().zip(v2).enumerate() {
+                explanation.deciding_path.push(i);
+                let ordering = explain_into(p1, p2, depth + 1, explanation);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                explanation.deciding_path.pop();
+            }
+
+            let ordering = v1.len().cmp(&v2.len());
+            let side = if ordering == Ordering::Less {
+                Side::Left
+            } else {
+                Side::Right
+            };
+            if ordering != Ordering::Equal {
+                explanation.deciding_path.push(v1.len().min(v2.len()));
+            }
+            decide(explanation, ordering, Step::RanOut(side))
+        }
+        (Packet::Integer(_), Packet::List(_)) => {
+            let packet = Packet::List(vec![left.clone()]);
+            explanation.steps.push((
+                depth + 1,
+                Step::Promote {
+                    side: Side::Left,
+                    packet: packet.clone(),
+                },
+            ));
+            explain_into(&packet, right, depth + 1, explanation)
+        }
+        (Packet::List(_), Packet::Integer(_)) => {
+            let packet = Packet::List(vec![right.clone()]);
+            explanation.steps.push((
+                depth + 1,
+                Step::Promote {
+                    side: Side::Right,
+                    packet: packet.clone(),
+                },
+            ));
+            explain_into(left, &packet, depth + 1, explanation)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    Expected {
+        expected: &'static str,
+        found: Option<char>,
+    },
+    TooDeep,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match self.kind {
+            ParseErrorKind::Expected {
+                expected,
+                found: Some(c),
+            } => write!(f, "expected {expected}, found {c:?}"),
+            ParseErrorKind::Expected {
+                expected,
+                found: None,
+            } => write!(f, "expected {expected}, found end of line"),
+            ParseErrorKind::TooDeep => write!(f, "lists nested more than {MAX_DEPTH} deep"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+struct Cursor<'a> {
+    text: &'a str,
+    line: usize,
+    position: usize,
+}
+
+impl Cursor<'_> {
+    fn peek(&mut self) -> Option<char> {
+        while let Some(c) = self.text[self.position..]
+            .chars()
+            .next()
+            .filter(|c| c.is_whitespace())
+        {
+            self.position += c.len_utf8();
+        }
+        self.text[self.position..].chars().next()
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: self.text[..self.position].chars().count() + 1,
+            kind,
+        }
+    }
+
+    fn expected(&mut self, expected: &'static str) -> ParseError {
+        let found = self.peek();
+        self.error(ParseErrorKind::Expected { expected, found })
+    }
+
+    fn packet(&mut self, depth: usize) -> Result<Packet, ParseError> {
+        match self.peek() {
+            Some('[') => self.list(depth),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.integer(),
+            _ => Err(self.expected("'[' or an integer")),
+        }
+    }
+
+    fn list(&mut self, depth: usize) -> Result<Packet, ParseError> {
+        if depth == MAX_DEPTH {
+            return Err(self.error(ParseErrorKind::TooDeep));
+        }
+
+        self.position += 1;
+        let mut packets = vec![];
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(Packet::List(packets));
+        }
+
+        loop {
+            packets.push(self.packet(depth + 1)?);
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(Packet::List(packets));
+                }
+                _ => return Err(self.expected("',' or ']'")),
+            }
+        }
+    }
+
+    fn integer(&mut self) -> Result<Packet, ParseError> {
+        let start = self.position;
+        if self.text[self.position..].starts_with('-') {
+            self.position += 1;
+        }
+
+        let digits = self.text[self.position..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.text.len() - self.position);
+        if digits == 0 {
+            return Err(self.expected("a digit"));
+        }
+
+        self.position += digits;
+        Ok(Packet::Integer(
+            self.text[start..self.position].parse().unwrap(),
+        ))
+    }
+}
+
+pub fn parse(text: &str, line: usize) -> Result<Packet, ParseError> {
+    let mut cursor = Cursor {
+        text,
+        line,
+        position: 0,
+    };
+    let packet = cursor.packet(0)?;
+    if cursor.peek().is_some() {
+        return Err(cursor.expected("end of line"));
+    }
+
+    Ok(packet)
+}
+
+impl FromStr for Packet {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck::{quickcheck, Arbitrary, Gen};
+
+    fn arbitrary_packet(g: &mut Gen, depth: usize) -> Packet {
+        if depth > 0 && u8::arbitrary(g) % 3 == 0 || depth > 6 {
+            let digits: String = (0..1 + usize::arbitrary(g) % 40)
+                .map(|_| char::from(b'0' + u8::arbitrary(g) % 10))
+                .collect();
+            let value: BigInt = digits.parse().unwrap();
+            return Packet::Integer(if bool::arbitrary(g) { -value } else { value });
+        }
+
+        let length = usize::arbitrary(g) % 5;
+        Packet::List(
+            (0..length)
+                .map(|_| arbitrary_packet(g, depth + 1))
+                .collect(),
+        )
+    }
+
+    impl Arbitrary for Packet {
+        fn arbitrary(g: &mut Gen) -> Self {
+            arbitrary_packet(g, 0)
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            match self {
+                Packet::Integer(_) => Box::new(std::iter::empty()),
+                Packet::List(packets) => Box::new(packets.shrink().map(Packet::List)),
+            }
+        }
+    }
+
+    fn expected(
+        line: usize,
+        column: usize,
+        expected: &'static str,
+        found: Option<char>,
+    ) -> ParseError {
+        ParseError {
+            line,
+            column,
+            kind: ParseErrorKind::Expected { expected, found },
+        }
+    }
+
+    quickcheck! {
+        fn display_round_trips(packet: Packet) -> bool {
+            let text = packet.to_string();
+            parse(&text, 1).is_ok_and(|parsed| parsed.is_identical(&packet) && parsed.to_string() == text)
+        }
+
+        fn explanation_agrees_with_ordering(left: Packet, right: Packet) -> bool {
+            left.explain(&right).ordering == left.cmp(&right)
+        }
+
+        fn whitespace_is_ignored(packet: Packet) -> bool {
+            let text = packet.to_string().replace(',', " ,\t").replace('[', "[ ");
+            parse(&text, 1).is_ok_and(|parsed| parsed.is_identical(&packet))
+        }
+    }
+
+    #[test]
+    fn explains_mixed_types() {
+        let left: Packet = "[[1],[2,3,4]]".parse().unwrap();
+        let right: Packet = "[[1],4]".parse().unwrap();
+        assert_eq!(
+            left.explain(&right).to_string(),
+            "\
+- Compare [[1],[2,3,4]] vs [[1],4]
+  - Compare [1] vs [1]
+    - Compare 1 vs 1
+  - Compare [2,3,4] vs 4
+    - Mixed types; convert right to [4] and retry comparison
+    - Compare [2,3,4] vs [4]
+      - Compare 2 vs 4
+        - Left side is smaller, so inputs are in the right order
+decided at element path [1, 0]"
+        );
+    }
+
+    #[test]
+    fn explains_running_out_and_equal_packets() {
+        let left: Packet = "[7,7,7,7]".parse().unwrap();
+        let right: Packet = "[7,7,7]".parse().unwrap();
+        let explanation = left.explain(&right);
+        assert_eq!(explanation.ordering, Ordering::Greater);
+        assert_eq!(explanation.deciding_path, [3]);
+        assert!(explanation
+            .to_string()
+            .contains("\n  - Right side ran out of items, so inputs are not in the right order\n"));
+
+        let explanation = left.explain(&left);
+        assert_eq!(explanation.ordering, Ordering::Equal);
+        assert!(explanation.to_string().ends_with("\npackets are equal"));
+    }
+
+    #[test]
+    fn integers_are_not_identical_to_lists() {
+        let integer: Packet = "1".parse().unwrap();
+        let list: Packet = "[1]".parse().unwrap();
+        assert_eq!(integer, list);
+        assert!(!integer.is_identical(&list));
+    }
+
+    #[test]
+    fn reports_unterminated_list() {
+        assert_eq!(parse("[1,2", 3), Err(expected(3, 5, "',' or ']'", None)));
+    }
+
+    #[test]
+    fn reports_missing_element() {
+        assert_eq!(
+            parse("[1,,2]", 1),
+            Err(expected(1, 4, "'[' or an integer", Some(',')))
+        );
+    }
+
+    #[test]
+    fn reports_trailing_text() {
+        assert_eq!(
+            parse("[1] x", 1),
+            Err(expected(1, 5, "end of line", Some('x')))
+        );
+    }
+
+    #[test]
+    fn reports_sign_without_digits() {
+        assert_eq!(parse("[-]", 1), Err(expected(1, 3, "a digit", Some(']'))));
+    }
+
+    #[test]
+    fn counts_columns_in_characters() {
+        assert_eq!(
+            parse("[é]", 1),
+            Err(expected(1, 2, "'[' or an integer", Some('é')))
+        );
+    }
+
+    #[test]
+    fn reports_excessive_nesting() {
+        let text = "[".repeat(MAX_DEPTH + 1);
+        assert_eq!(
+            parse(&text, 1),
+            Err(ParseError {
+                line: 1,
+                column: MAX_DEPTH + 1,
+                kind: ParseErrorKind::TooDeep,
+            })
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use crate::Position;
+use std::collections::HashSet;
+
+pub trait Occupancy {
+    fn contains(&self, position: Position) -> bool;
+    fn insert(&mut self, position: Position);
+}
+
+impl Occupancy for HashSet<Position> {
+    fn contains(&self, position: Position) -> bool {
+        HashSet::contains(self, &position)
+    }
+
+    fn insert(&mut self, position: Position) {
+        HashSet::insert(self, position);
+    }
+}
+
+pub struct DenseGrid {
+    min_x: i32,
+    width: usize,
+    height: usize,
+    cells: Vec<bool>,
+}
+
+impl DenseGrid {
+    pub fn new(min_x: i32, max_x: i32, max_y: i32) -> Self {
+        let width = (max_x - min_x + 1) as usize;
+        let height = (max_y + 1) as usize;
+        DenseGrid {
+            min_x,
+            width,
+            height,
+            cells: vec![false; width * height],
+        }
+    }
+
+    fn index(&self, position: Position) -> Option<usize> {
+        let x = usize::try_from(position.x - self.min_x).ok()?;
+        let y = usize::try_from(position.y).ok()?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+}
+
+impl Occupancy for DenseGrid {
+    fn contains(&self, position: Position) -> bool {
+        self.index(position).is_some_and(|index| self.cells[index])
+    }
+
+    fn insert(&mut self, position: Position) {
+        let index = self
+            .index(position)
+            .expect("sand settled outside the dense grid");
+        self.cells[index] = true;
+    }
+}
@@ -1,17 +1,20 @@
+use crate::grid::{DenseGrid, Occupancy};
 use itertools::{iproduct, izip};
 use std::cmp::{max, min};
 use std::collections::HashSet;
 use std::fs::read_to_string;
 
-struct Cave {
+mod grid;
+
+struct Cave<O: Occupancy> {
     has_floor: bool,
-    positions: HashSet<Position>,
+    positions: O,
     max_y: i32,
 }
 
-impl Cave {
+impl<O: Occupancy> Cave<O> {
     fn is_blocked(&self, position: Position) -> bool {
-        if self.positions.contains(&position) {
+        if self.positions.contains(position) {
             return true;
         }
         self.has_floor && position.y >= self.max_y + 2
@@ -34,7 +37,7 @@ impl Default for Position {
 }
 
 impl Position {
-    fn step(self, cave: &Cave) -> Option<Self> {
+    fn step<O: Occupancy>(self, cave: &Cave<O>) -> Option<Self> {
         let mut new_position = Position {
             y: self.y + 1,
             ..self
@@ -57,13 +60,6 @@ impl Position {
         None
     }
 
-    fn path(self, cave: &Cave) -> Path {
-        Path {
-            current_position: self,
-            cave,
-        }
-    }
-
     fn get_between_points(&self, other: Self) -> impl Iterator<Item = Position> {
         let (min_x, max_x) = (min(self.x, other.x), max(self.x, other.x));
         let (min_y, max_y) = (min(self.y, other.y), max(self.y, other.y));
@@ -76,38 +72,8 @@ impl Position {
     }
 }
 
-#[derive(Clone)]
-struct Path<'a> {
-    cave: &'a Cave,
-    current_position: Position,
-}
-
-impl<'a> Iterator for Path<'a> {
-    type Item = Position;
-    fn next(&mut self) -> Option<Self::Item> {
-        self.current_position = self.current_position.step(self.cave)?;
-        Some(self.current_position)
-    }
-}
-
-impl<'a> From<&'a Cave> for Path<'a> {
-    fn from(value: &'a Cave) -> Self {
-        Position::default().path(value)
-    }
-}
-
-impl<'a> Path<'a> {
-    fn end_point(&mut self) -> Option<Position> {
-        if self.clone().any(|p| self.cave.will_fall_forever(p)) {
-            None
-        } else {
-            self.last()
-        }
-    }
-}
-
-fn get_cave(input: &str, has_floor: bool) -> Cave {
-    let positions: HashSet<Position> = input
+fn get_rocks(input: &str) -> Vec<Position> {
+    input
         .lines()
         .flat_map(|line| {
             let positions = line.split(" -> ").map(|s| {
@@ -120,36 +86,80 @@ fn get_cave(input: &str, has_floor: bool) -> Cave {
             izip!(positions.clone().skip(1), positions)
                 .flat_map(|(p1, p2)| p1.get_between_points(p2))
         })
-        .collect();
+        .collect()
+}
+
+fn get_dense_grid(rocks: &[Position]) -> DenseGrid {
+    let source = Position::default();
+    let floor_y = rocks.iter().map(|p| p.y).max().unwrap() + 2;
+    let min_x = rocks.iter().map(|p| p.x).min().unwrap();
+    let max_x = rocks.iter().map(|p| p.x).max().unwrap();
+    DenseGrid::new(
+        min(min_x, source.x - floor_y),
+        max(max_x, source.x + floor_y),
+        floor_y - 1,
+    )
+}
+
+fn get_cave<O: Occupancy>(rocks: &[Position], has_floor: bool, mut positions: O) -> Cave<O> {
+    for &rock in rocks {
+        positions.insert(rock);
+    }
 
     Cave {
-        max_y: positions.iter().map(|p| p.y).max().unwrap(),
+        max_y: rocks.iter().map(|p| p.y).max().unwrap(),
         positions,
         has_floor,
     }
 }
 
-fn count_grains(mut cave: Cave) -> u32 {
-    let mut current_grain: Path = (&cave).into();
+fn count_grains<O: Occupancy>(mut cave: Cave<O>) -> u32 {
+    let mut path = vec![Position::default()];
     let mut grain_count = 0;
-    while let Some(end) = current_grain.end_point() {
-        cave.positions.insert(end);
-        grain_count += 1;
-        if end.y == 0 {
+    while let Some(&position) = path.last() {
+        if cave.will_fall_forever(position) {
             break;
         }
-        current_grain = (&cave).into();
+
+        match position.step(&cave) {
+            Some(next) => path.push(next),
+            None => {
+                cave.positions.insert(position);
+                grain_count += 1;
+                if position.y == 0 {
+                    break;
+                }
+                path.pop();
+            }
+        }
     }
     grain_count
 }
 
 fn main() {
     let input = read_to_string("input").unwrap();
-    let cave_without_floor = get_cave(&input, false);
-    let cave_with_floor = get_cave(&input, true);
-
-    let output_1 = count_grains(cave_without_floor);
-    let output_2 = count_grains(cave_with_floor);
+    let rocks = get_rocks(&input);
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (output_1, output_2) = match args
+        .iter()
+        .map(|s| s.as_str())
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [] => (
+            count_grains(get_cave(&rocks, false, get_dense_grid(&rocks))),
+            count_grains(get_cave(&rocks, true, get_dense_grid(&rocks))),
+        ),
+        ["sparse"] => (
+            count_grains(get_cave(&rocks, false, HashSet::new())),
+            count_grains(get_cave(&rocks, true, HashSet::new())),
+        ),
+        _ => {
+            eprintln!("usage: day_14 [sparse]");
+            return;
+        }
+    };
 
     println!("part 1: {output_1} part 2: {output_2}");
 }